@@ -1,41 +1,116 @@
 use eframe::egui;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use chrono::Local;
 
-use crate::pin::Pin;
+use crate::autosave::Autosave;
+use crate::cli;
+use crate::crypto;
+use crate::bundle::{self, ImportAction, ImportPlan};
+use crate::folder_sync::{self, FolderSync};
+use crate::history::{Edit, History, HistoryAction, Snapshot};
+use crate::images;
+use crate::importers;
+use crate::ipc::{self, Incoming, Request};
+use crate::markdown;
+use crate::migrate::{self, SCHEMA_VERSION};
+use crate::palette::{self, Palette, PaletteColor};
+use crate::pin::{self, Layout, Pin};
+use crate::recovery::{self, RecoveryReport};
+use crate::settings::Settings;
+use crate::shutdown::Shutdown;
+use crate::timefmt;
+use crate::store::{self, PinStore};
 use crate::timer::TimerState;
+use crate::todotxt::{self, Task};
+use crate::trash::{self, TrashedPin};
 use tray_icon::{
     menu::{Menu, MenuEvent, MenuItem},
     TrayIconBuilder, TrayIcon
 };
 
-#[derive(Serialize, Deserialize)]
+#[derive(Default, PartialEq)]
+pub enum DashboardView {
+    #[default]
+    Pins,
+    Archive,
+    Trash,
+    Settings,
+}
+
+#[derive(Serialize, Deserialize, Default)]
 pub struct AppState {
+    #[serde(default)]
+    pub schema_version: u64,
     pub pins: Vec<Pin>,
     pub global_timer: TimerState,
+    #[serde(default)]
+    pub archive: Vec<Pin>,
+    #[serde(default)]
+    pub trash: Vec<TrashedPin>,
+    #[serde(default)]
+    pub settings: Settings,
+    #[serde(skip)]
+    pub view: DashboardView,
+    /// Kept in the config file rather than the board (see palette.rs).
+    #[serde(skip)]
+    pub palette: Palette,
+    /// Written along with the board by the next save.
+    #[serde(skip)]
+    pub palette_dirty: bool,
+    #[serde(skip)]
+    pub history: History,
+    #[serde(skip)]
+    pub autosave: Autosave,
+    #[serde(skip)]
+    pub shutdown: Shutdown,
+    #[serde(skip)]
+    pub requests: Option<std::sync::mpsc::Receiver<Incoming>>,
+    #[serde(skip)]
+    pub archive_query: String,
+    /// Only pins with this tag show on the dashboard.
+    #[serde(skip)]
+    pub tag_filter: Option<String>,
+    #[serde(skip)]
+    pub notice: Option<String>,
+    #[serde(skip)]
+    pub import_plan: Option<ImportPlan>,
+    #[serde(skip)]
+    pub todo_mirror: Option<todotxt::Mirror>,
+    #[serde(skip)]
+    pub folder_sync: Option<FolderSync>,
     #[serde(skip)]
     pub new_pin_content: String,
     #[serde(skip)]
     pub new_pin_minutes: u64,
     #[serde(skip)]
     pub _tray: Option<TrayIcon>,
-}
-
-impl Default for AppState {
-    fn default() -> Self {
-        Self {
-            pins: Vec::new(),
-            global_timer: TimerState::default(),
-            new_pin_content: String::new(),
-            new_pin_minutes: 0,
-            _tray: None,
-        }
-    }
+    #[serde(skip)]
+    pub store: Option<Box<dyn PinStore>>,
+    #[serde(skip)]
+    pub load_error: Option<String>,
+    #[serde(skip)]
+    pub recovery: Option<RecoveryReport>,
+    /// Set while the board is encrypted and unlocked; every save is sealed with it.
+    #[serde(skip)]
+    pub key: Option<crypto::Key>,
+    /// Nothing of the board is in memory and nothing is saved until it is unlocked.
+    #[serde(skip)]
+    pub locked: bool,
+    #[serde(skip)]
+    pub unlock_prompt: bool,
+    #[serde(skip)]
+    pub passphrase_input: zeroize::Zeroizing<String>,
+    #[serde(skip)]
+    pub passphrase_confirm: zeroize::Zeroizing<String>,
+    #[serde(skip)]
+    pub passphrase_error: Option<String>,
+    #[serde(skip)]
+    pub last_activity: Option<Instant>,
 }
 
 impl AppState {
-    pub fn new(cc: &eframe::CreationContext) -> Self {
+    pub fn new(cc: &eframe::CreationContext, instance: Option<ipc::Instance>) -> Self {
         let mut visuals = egui::Visuals::dark();
         visuals.window_rounding = egui::Rounding::same(16.0);
         visuals.window_shadow = egui::epaint::Shadow {
@@ -64,33 +139,697 @@ impl AppState {
             .build()
             .ok();
 
-        // Tray Event Listener
+        // Tray quit and SIGTERM/SIGINT both go through the egui loop, which saves first.
+        let shutdown = Shutdown::default();
+        let (tray_shutdown, ctx) = (shutdown.clone(), cc.egui_ctx.clone());
         std::thread::spawn(move || {
             let receiver = MenuEvent::receiver();
-            loop {
-                if let Ok(event) = receiver.try_recv() {
-                    if event.id == "quit" { std::process::exit(0); }
-                }
-                std::thread::sleep(std::time::Duration::from_millis(200));
+            while let Ok(event) = receiver.recv() {
+                if event.id == "quit" { tray_shutdown.request(&ctx); }
             }
         });
+        if let Err(e) = shutdown.listen_for_signals(cc.egui_ctx.clone()) {
+            eprintln!("Failed to listen for signals: {e}");
+        }
 
-        if let Some(storage) = cc.storage {
-             if let Some(value) = storage.get_string("pinboard_state") {
-                 let mut app: AppState = serde_json::from_str(&value).unwrap_or_default();
-                 app._tray = tray;
-                 for pin in &mut app.pins {
-                     if pin.opacity == 0.0 { pin.opacity = 0.95; }
-                 }
-                 return app;
-             }
+        let mut store = store::open_default();
+        let saved = match store.as_ref().map(|s| s.load()) {
+            Some(Ok(Some(text))) => Ok(Some(text)),
+            Some(Err(e)) => Err(e),
+            // First run with this backend: pick up the board from wherever it was kept before.
+            _ => Ok(store::previous_board()),
+        };
+
+        let mut loaded = false;
+        let mut app = match saved {
+            Ok(Some(text)) if crypto::is_sealed(&text) => AppState { locked: true, unlock_prompt: true, ..Default::default() },
+            Ok(Some(text)) => match Self::parse_state(&text) {
+                Ok(app) => {
+                    loaded = store.is_some();
+                    app
+                }
+                Err(e) => Self::recover(&text, &text, e.as_ref(), store.as_mut()),
+            },
+            Ok(None) => AppState::default(),
+            Err(e) => Self::unreadable(&e, store.as_deref()),
+        };
+        if !app.locked {
+            app.open_board(&cc.egui_ctx);
         }
-        
-        let mut app = AppState::default();
+        if loaded {
+            app.remove_unused_images();
+        }
+        app.palette = palette::current();
         app._tray = tray;
+        app.store = store;
+        app.shutdown = shutdown;
+        app.requests = instance.map(|i| i.listen(cc.egui_ctx.clone()));
+        app
+    }
+
+    /// Starts what runs alongside a loaded board.
+    fn open_board(&mut self, ctx: &egui::Context) {
+        self.schema_version = SCHEMA_VERSION;
+        if trash::purge_expired(&mut self.trash, self.settings.trash_retention_days) { self.changed(); }
+        self.history.reset(Snapshot::capture(&self.pins, &self.archive, &self.trash));
+        self.todo_mirror = self.settings.todo_txt_mirror.clone().map(todotxt::Mirror::new);
+        if let Some(dir) = self.settings.markdown_folder.clone() {
+            self.start_folder_sync(dir, ctx);
+        }
+        self.last_activity = Some(Instant::now());
+    }
+
+    /// Only call this with a board that was read and parsed in full: pins
+    /// missing from anything less would lose their images.
+    fn remove_unused_images(&self) {
+        let pins = self.pins.iter().chain(&self.archive).chain(self.trash.iter().map(|t| &t.pin));
+        images::remove_unused(pins.filter_map(|p| p.image.as_deref()));
+    }
+
+    /// Acts on a request from another launch.
+    fn handle_request(&mut self, ctx: &egui::Context, request: Request) -> ipc::Reply {
+        match request {
+            Request::Show => {
+                if self.locked { self.unlock_prompt = true; }
+                ctx.send_viewport_cmd_to(egui::ViewportId::ROOT, egui::ViewportCommand::Visible(true));
+                ctx.send_viewport_cmd_to(egui::ViewportId::ROOT, egui::ViewportCommand::Minimized(false));
+                ctx.send_viewport_cmd_to(egui::ViewportId::ROOT, egui::ViewportCommand::Focus);
+                Ok(String::new())
+            }
+            _ if self.locked => Err("Pin-Board is locked; unlock it first".to_string()),
+            request => {
+                let reply = cli::apply(request, &mut self.pins);
+                self.edited(Edit::Step);
+                reply
+            }
+        }
+    }
+
+    fn parse_state(text: &str) -> Result<AppState, Box<dyn std::error::Error>> {
+        let mut doc: serde_json::Value = serde_json::from_str(text)?;
+        migrate::migrate(&mut doc)?;
+        Ok(serde_json::from_value(doc)?)
+    }
+
+    /// Salvages what it can from `text`. `raw` is what was stored, which
+    /// differs from `text` when the board was encrypted.
+    fn recover(text: &str, raw: &str, error: &dyn std::error::Error, store: Option<&mut Box<dyn PinStore>>) -> AppState {
+        eprintln!("Failed to load saved board: {error}");
+        let salvage = recovery::salvage(text);
+        let mut app = AppState {
+            pins: salvage.pins,
+            archive: salvage.archive,
+            trash: salvage.trash,
+            settings: salvage.settings.unwrap_or_default(),
+            ..Default::default()
+        };
+
+        // Never overwrite a board we could not read until it is safely set aside.
+        let quarantined = match store.map(|s| s.quarantine(raw)) {
+            Some(Ok(path)) => Some(path),
+            Some(Err(e)) => {
+                eprintln!("Failed to quarantine saved board, saving is disabled: {e}");
+                app.load_error = Some(e.to_string());
+                None
+            }
+            None => {
+                app.load_error = Some("no data directory".to_string());
+                None
+            }
+        };
+
+        app.recovery = Some(RecoveryReport {
+            error: error.to_string(),
+            quarantined,
+            recovered: salvage.recovered,
+            lost: salvage.lost,
+        });
         app
     }
 
+    /// An empty board that never saves, for when the stored one could not
+    /// even be read. Whatever is there stays as it was.
+    fn unreadable(error: &dyn std::error::Error, store: Option<&dyn PinStore>) -> AppState {
+        let path = store.map(|s| s.path().display().to_string()).unwrap_or_default();
+        eprintln!("Failed to read {path}: {error}");
+        AppState {
+            load_error: Some(format!("{path} could not be read")),
+            recovery: Some(RecoveryReport {
+                error: error.to_string(),
+                quarantined: None,
+                recovered: Vec::new(),
+                lost: Vec::new(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn render_board_menu(&mut self, ui: &mut egui::Ui) {
+        ui.set_min_width(180.0);
+
+        if ui.button("Export board…").clicked() {
+            ui.close_menu();
+            let path = rfd::FileDialog::new()
+                .add_filter("Pin-Board bundle", &["json"])
+                .set_file_name("pin-board.json")
+                .save_file();
+            if let Some(path) = path {
+                let written = bundle::export(&self.pins)
+                    .map_err(|e| e.to_string())
+                    .and_then(|text| std::fs::write(&path, text).map_err(|e| e.to_string()));
+                self.notice = Some(match written {
+                    Ok(()) => format!("Exported {} pins to {}", self.pins.len(), path.display()),
+                    Err(e) => format!("Export failed: {e}"),
+                });
+            }
+        }
+        if ui.button("Import board…").clicked() {
+            ui.close_menu();
+            if let Some(path) = rfd::FileDialog::new().add_filter("Pin-Board bundle", &["json"]).pick_file() {
+                let parsed = std::fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|text| bundle::parse(&text));
+                match parsed {
+                    Ok(pins) => {
+                        self.import_plan = Some(ImportPlan::new(path, self.pins.iter().chain(self.archive.iter()), pins));
+                    }
+                    Err(e) => self.notice = Some(format!("Import failed: {e}")),
+                }
+            }
+        }
+
+        ui.separator();
+
+        if ui.button("Export Markdown…").clicked() {
+            ui.close_menu();
+            let path = rfd::FileDialog::new()
+                .add_filter("Markdown", &["md"])
+                .set_file_name("pin-board.md")
+                .save_file();
+            if let Some(path) = path {
+                self.notice = Some(match std::fs::write(&path, markdown::export(&self.pins)) {
+                    Ok(()) => format!("Exported {} pins to {}", self.pins.len(), path.display()),
+                    Err(e) => format!("Export failed: {e}"),
+                });
+            }
+        }
+        if ui.button("Copy as Markdown").clicked() {
+            ui.close_menu();
+            ui.ctx().copy_text(markdown::export(&self.pins));
+            self.notice = Some(format!("Copied {} pins as Markdown", self.pins.len()));
+        }
+        if ui.button("Import Markdown…").clicked() {
+            ui.close_menu();
+            if let Some(path) = rfd::FileDialog::new().add_filter("Markdown", &["md", "markdown", "txt"]).pick_file() {
+                self.notice = Some(match std::fs::read_to_string(&path) {
+                    Ok(text) => {
+                        let (added, updated) = self.merge_pins(markdown::import(&text));
+                        format!("Imported {} ({added} added, {updated} updated)", path.display())
+                    }
+                    Err(e) => format!("Import failed: {e}"),
+                });
+            }
+        }
+
+        ui.separator();
+        if ui.button("Export todo.txt…").clicked() {
+            ui.close_menu();
+            let path = rfd::FileDialog::new().add_filter("todo.txt", &["txt"]).set_file_name("todo.txt").save_file();
+            if let Some(path) = path {
+                self.notice = Some(match std::fs::write(&path, todotxt::export(&self.pins)) {
+                    Ok(()) => format!("Exported {} pins to {}", self.pins.len(), path.display()),
+                    Err(e) => format!("Export failed: {e}"),
+                });
+            }
+        }
+        if ui.button("Import todo.txt…").clicked() {
+            ui.close_menu();
+            if let Some(path) = rfd::FileDialog::new().add_filter("todo.txt", &["txt"]).pick_file() {
+                self.notice = Some(match std::fs::read_to_string(&path) {
+                    Ok(text) => {
+                        let (added, updated) = self.apply_tasks(todotxt::parse(&text));
+                        format!("Imported {} ({added} added, {updated} updated)", path.display())
+                    }
+                    Err(e) => format!("Import failed: {e}"),
+                });
+            }
+        }
+
+        ui.separator();
+        ui.menu_button("Import from", |ui| {
+            for source in importers::Source::ALL {
+                if ui.button(source.label()).clicked() {
+                    ui.close_menu();
+                    self.import_from(source);
+                }
+            }
+        });
+    }
+
+    fn import_from(&mut self, source: importers::Source) {
+        let mut dialog = rfd::FileDialog::new();
+        if let Some(location) = source.default_location() {
+            let dir = if source.reads_folder() { location.as_path() } else { location.parent().unwrap_or(&location) };
+            dialog = dialog.set_directory(dir);
+        }
+        let path = if source.reads_folder() { dialog.pick_folder() } else { dialog.pick_file() };
+        let Some(path) = path else { return };
+
+        self.notice = Some(match source.import(&path) {
+            Ok(pins) => {
+                let (added, updated) = self.merge_pins(pins);
+                format!("Imported {} ({added} added, {updated} updated)", path.display())
+            }
+            Err(e) => format!("Import failed: {e}"),
+        });
+    }
+
+    fn render_import_preview(&mut self, ctx: &egui::Context) {
+        let Some(plan) = &self.import_plan else { return };
+        let mut open = true;
+        let mut apply = false;
+        let mut cancel = false;
+
+        egui::Window::new("Import board")
+            .collapsible(false)
+            .resizable(true)
+            .default_width(320.0)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(plan.source.display().to_string()).size(10.0).color(egui::Color32::from_gray(120)));
+                ui.add_space(8.0);
+
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    let groups = [
+                        (ImportAction::Add, "ADDED", egui::Color32::from_rgb(52, 211, 153)),
+                        (ImportAction::Update, "UPDATED", egui::Color32::from_rgb(80, 160, 255)),
+                        (ImportAction::Skip, "SKIPPED (ours is newer)", egui::Color32::from_gray(100)),
+                    ];
+                    for (action, label, color) in groups {
+                        let count = plan.count(action);
+                        if count == 0 { continue; }
+                        ui.label(egui::RichText::new(format!("{label} · {count}")).size(10.0).strong().color(color));
+                        for (pin, _) in plan.entries.iter().filter(|(_, a)| *a == action) {
+                            let text = if pin.title.is_empty() { &pin.content } else { &pin.title };
+                            let preview = text.lines().next().unwrap_or("").chars().take(40).collect::<String>();
+                            ui.label(egui::RichText::new(preview).size(12.0).color(egui::Color32::from_gray(200)));
+                        }
+                        ui.add_space(8.0);
+                    }
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let changes = plan.count(ImportAction::Add) + plan.count(ImportAction::Update);
+                    if ui.add_enabled(changes > 0, egui::Button::new("Apply")).clicked() { apply = true; }
+                    if ui.button("Cancel").clicked() { cancel = true; }
+                });
+            });
+
+        if apply {
+            if let Some(plan) = self.import_plan.take() {
+                let pins = plan.entries.into_iter().filter(|(_, a)| *a != ImportAction::Skip).map(|(p, _)| p).collect();
+                let (added, updated) = self.merge_pins(pins);
+                self.notice = Some(format!("Imported {} ({added} added, {updated} updated)", plan.source.display()));
+            }
+        }
+        if cancel || !open { self.import_plan = None; }
+    }
+
+    /// Merges pins by `id`. Pins already on the board or in the archive take the
+    /// incoming content but keep their window where it is.
+    fn merge_pins(&mut self, incoming: Vec<Pin>) -> (usize, usize) {
+        let (mut added, mut updated) = (0, 0);
+        for pin in incoming {
+            match self.pins.iter_mut().chain(self.archive.iter_mut()).find(|p| p.id == pin.id) {
+                Some(existing) => {
+                    let (layout, visible) = (existing.layout(), existing.visible);
+                    *existing = pin;
+                    existing.set_layout(layout);
+                    existing.visible = visible;
+                    updated += 1;
+                }
+                None => {
+                    self.pins.push(pin);
+                    added += 1;
+                }
+            }
+        }
+        self.edited(Edit::Step);
+        (added, updated)
+    }
+
+    /// Tasks only carry titles, completion and due dates, so known pins are
+    /// updated in place rather than replaced.
+    fn apply_tasks(&mut self, tasks: Vec<Task>) -> (usize, usize) {
+        let (mut added, mut updated) = (0, 0);
+        for task in tasks {
+            let existing = self.pins.iter_mut().chain(self.archive.iter_mut()).find(|p| task.id.as_ref() == Some(&p.id));
+            match existing {
+                Some(pin) => {
+                    if task.apply(pin) { updated += 1; }
+                }
+                None => {
+                    self.pins.push(task.into_pin());
+                    added += 1;
+                }
+            }
+        }
+        if added + updated > 0 { self.edited(Edit::Step); }
+        (added, updated)
+    }
+
+    fn sync_todo_txt(&mut self) {
+        let Some(mut mirror) = self.todo_mirror.take() else { return };
+        if mirror.due() {
+            match mirror.read_changes() {
+                Ok(Some(changes)) => {
+                    self.apply_tasks(changes.tasks);
+                    for id in changes.removed {
+                        if let Some(idx) = self.pins.iter().position(|p| p.id == id) { self.trash_pin(idx); }
+                    }
+                    self.edited(Edit::Step);
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("Failed to read {}: {e}", mirror.path().display()),
+            }
+            if let Err(e) = mirror.write(&self.pins) {
+                eprintln!("Failed to write {}: {e}", mirror.path().display());
+            }
+        }
+        self.todo_mirror = Some(mirror);
+    }
+
+    fn start_folder_sync(&mut self, dir: std::path::PathBuf, ctx: &egui::Context) {
+        match FolderSync::start(dir.clone(), ctx) {
+            Ok(sync) => self.folder_sync = Some(sync),
+            Err(e) => {
+                eprintln!("Failed to sync {}: {e}", dir.display());
+                self.notice = Some(format!("Folder sync failed: {e}"));
+            }
+        }
+    }
+
+    fn sync_folder(&mut self) {
+        let Some(mut sync) = self.folder_sync.take() else { return };
+        let changes = sync.changes();
+        let edited = !changes.is_empty();
+        for change in changes {
+            match change {
+                folder_sync::Change::Edited { id, text } => {
+                    // A file for an archived pin brings it back onto the board.
+                    if let Some(idx) = self.archive.iter().position(|p| p.id == id) {
+                        let pin = self.archive.remove(idx);
+                        self.pins.push(pin);
+                    }
+                    let pin = match self.pins.iter().position(|p| p.id == id) {
+                        Some(idx) => &mut self.pins[idx],
+                        None => {
+                            let mut pin = Pin::new(String::new(), String::new(), None);
+                            pin.id = id;
+                            self.pins.push(pin);
+                            self.pins.last_mut().unwrap()
+                        }
+                    };
+                    folder_sync::apply(&text, pin);
+                    sync.adopt(pin);
+                }
+                folder_sync::Change::Removed(id) => {
+                    if let Some(idx) = self.pins.iter().position(|p| p.id == id) { self.trash_pin(idx); }
+                }
+            }
+        }
+        sync.write(&self.pins);
+        self.folder_sync = Some(sync);
+        if edited { self.edited(Edit::Step); }
+    }
+
+    /// A new pin showing the image `result` names.
+    fn pin_image(&mut self, result: Result<String, impl std::fmt::Display>) {
+        match result {
+            Ok(name) => {
+                let mut pin = Pin::new(String::new(), String::new(), None);
+                pin.image = Some(name);
+                self.pins.push(pin);
+                self.edited(Edit::Step);
+            }
+            Err(e) => self.notice = Some(format!("Could not pin the image: {e}")),
+        }
+    }
+
+    /// Image files dropped onto the dashboard become pins.
+    fn pin_dropped_images(&mut self, ctx: &egui::Context) {
+        let dropped: Vec<_> = ctx.input(|i| i.raw.dropped_files.iter().filter_map(|f| f.path.clone()).collect());
+        for path in dropped {
+            if images::is_image(&path) {
+                self.pin_image(images::import(&path));
+            } else {
+                self.notice = Some(format!("{} is not an image", path.display()));
+            }
+        }
+    }
+
+    fn render_notice(&mut self, ui: &mut egui::Ui) {
+        let Some(notice) = &self.notice else { return };
+        let mut dismiss = false;
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new(notice).size(11.0).color(egui::Color32::from_gray(160)));
+            if ui.small_button("×").clicked() { dismiss = true; }
+        });
+        if dismiss { self.notice = None; }
+        ui.add_space(12.0);
+    }
+
+    fn render_recovery_banner(&mut self, ui: &mut egui::Ui) {
+        let Some(report) = &self.recovery else { return };
+        let mut dismiss = false;
+
+        egui::Frame::none()
+            .fill(egui::Color32::from_rgb(60, 20, 24))
+            .inner_margin(12.0)
+            .rounding(12.0)
+            .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(251, 113, 133)))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("BOARD RECOVERED").size(10.0).strong().color(egui::Color32::from_rgb(251, 113, 133)));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("Dismiss").clicked() { dismiss = true; }
+                    });
+                });
+                ui.label(egui::RichText::new(format!("The saved board could not be read ({}).", report.error)).size(11.0).color(egui::Color32::from_gray(200)));
+
+                match (&report.quarantined, &self.load_error) {
+                    (Some(path), _) => {
+                        ui.label(egui::RichText::new(format!("Original kept at {}", path.display())).size(10.0).color(egui::Color32::from_gray(140)));
+                    }
+                    (None, Some(e)) => {
+                        ui.label(egui::RichText::new(format!("Saving is paused so the original is not overwritten ({e}).")).size(10.0).color(egui::Color32::from_rgb(251, 191, 36)));
+                    }
+                    _ => {}
+                }
+
+                ui.add_space(4.0);
+                let summary = format!("{} recovered, {} lost", report.recovered.len(), report.lost.len());
+                egui::CollapsingHeader::new(egui::RichText::new(summary).size(11.0))
+                    .id_salt("recovery_details")
+                    .show(ui, |ui| {
+                        for title in &report.recovered {
+                            ui.label(egui::RichText::new(format!("✓ {title}")).size(11.0).color(egui::Color32::from_rgb(52, 211, 153)));
+                        }
+                        for title in &report.lost {
+                            ui.label(egui::RichText::new(format!("✗ {title}")).size(11.0).color(egui::Color32::from_rgb(251, 113, 133)));
+                        }
+                    });
+            });
+
+        if dismiss { self.recovery = None; }
+        ui.add_space(16.0);
+    }
+
+    fn persist(&mut self) {
+        if std::mem::take(&mut self.palette_dirty) {
+            if let Err(e) = self.palette.save() {
+                eprintln!("Failed to save palette: {e}");
+            }
+        }
+        let Some(value) = self.to_document() else { return };
+        self.write(&value);
+        self.autosave.saved();
+    }
+
+    /// Call after changing anything that is saved with the board.
+    fn changed(&mut self) {
+        self.autosave.mark();
+    }
+
+    /// Call after changing pins, the archive or the trash, which can be undone.
+    fn edited(&mut self, edit: Edit) {
+        self.history.record(Snapshot::capture(&self.pins, &self.archive, &self.trash), edit);
+        self.changed();
+    }
+
+    /// Saves shortly after the board changes, so a crash or a hard exit
+    /// loses at most a second of edits.
+    fn autosave(&mut self, ctx: &egui::Context) {
+        if self.autosave.due() {
+            self.persist();
+        }
+        if let Some(wait) = self.autosave.wait() {
+            ctx.request_repaint_after(wait);
+        }
+    }
+
+    fn to_document(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(self).map_err(|e| eprintln!("Failed to serialize board: {e}")).ok()
+    }
+
+    fn write(&mut self, value: &serde_json::Value) {
+        if self.load_error.is_some() || self.locked { return; }
+        let sealed = match &self.key {
+            Some(key) => match key.seal(value) {
+                Ok(sealed) => Some(sealed),
+                Err(e) => {
+                    eprintln!("Failed to encrypt board, not saving: {e}");
+                    return;
+                }
+            },
+            None => None,
+        };
+        let value = sealed.as_ref().unwrap_or(value);
+        if let Some(store) = &mut self.store {
+            if let Err(e) = store.save(value) {
+                eprintln!("Failed to save {}: {e}", store.path().display());
+            }
+        }
+    }
+
+    /// Saves, then drops the board from memory and closes every pin. Not
+    /// while saving is paused, when that would throw the board away.
+    fn lock(&mut self) {
+        if self.load_error.is_some() { return; }
+        self.persist();
+        let AppState { _tray, store, shutdown, requests, palette, .. } = std::mem::take(self);
+        *self = AppState { _tray, store, shutdown, requests, palette, locked: true, ..Default::default() };
+    }
+
+    fn unlock(&mut self, ctx: &egui::Context) {
+        let saved = match self.store.as_ref().map(|s| s.load()) {
+            Some(Ok(Some(text))) => Some(text),
+            Some(Err(e)) => {
+                self.passphrase_error = Some(e.to_string());
+                return;
+            }
+            _ => store::previous_board(),
+        };
+        let Some(sealed) = saved else {
+            self.passphrase_error = Some("The saved board is gone.".to_string());
+            return;
+        };
+
+        match crypto::open(&sealed, &self.passphrase_input) {
+            Ok((text, key)) => {
+                let (board, loaded) = match Self::parse_state(&text) {
+                    Ok(board) => (board, true),
+                    Err(e) => (Self::recover(&text, &sealed, e.as_ref(), self.store.as_mut()), false),
+                };
+                let AppState { _tray, store, shutdown, requests, palette, .. } = std::mem::take(self);
+                *self = AppState { _tray, store, shutdown, requests, palette, key: Some(key), ..board };
+                self.open_board(ctx);
+                if loaded { self.remove_unused_images(); }
+            }
+            Err(e) => {
+                self.passphrase_error = Some(format!("Could not unlock: {e}."));
+                self.passphrase_input = Default::default();
+            }
+        }
+    }
+
+    /// Encrypts the board under the typed passphrase, or re-encrypts it
+    /// under a new one.
+    fn set_passphrase(&mut self) {
+        self.passphrase_error = if self.load_error.is_some() {
+            Some("Saving is paused, so the board cannot be encrypted.".to_string())
+        } else if self.passphrase_input.is_empty() {
+            Some("Enter a passphrase.".to_string())
+        } else if self.passphrase_input != self.passphrase_confirm {
+            Some("The passphrases do not match.".to_string())
+        } else {
+            None
+        };
+        if self.passphrase_error.is_some() { return; }
+
+        match crypto::Key::new(&self.passphrase_input) {
+            Ok(key) => {
+                let first = self.key.is_none();
+                self.key = Some(key);
+                self.persist();
+                if let Some(store) = &mut self.store {
+                    if let Err(e) = store.scrub() {
+                        eprintln!("Failed to remove old copies of {}: {e}", store.path().display());
+                    }
+                }
+                self.notice = Some(if first { "Board encrypted." } else { "Passphrase changed." }.to_string());
+                self.passphrase_input = Default::default();
+                self.passphrase_confirm = Default::default();
+            }
+            Err(e) => self.passphrase_error = Some(e.to_string()),
+        }
+    }
+
+    /// Locks an encrypted board once nothing has been typed or clicked for
+    /// `auto_lock_minutes`.
+    fn auto_lock(&mut self, ctx: &egui::Context) {
+        if self.key.is_none() || self.load_error.is_some() || self.settings.auto_lock_minutes == 0 { return; }
+        let limit = Duration::from_secs(u64::from(self.settings.auto_lock_minutes) * 60);
+        let idle = self.last_activity.map_or(Duration::ZERO, |t| t.elapsed());
+        if idle >= limit {
+            self.lock();
+            ctx.request_repaint();
+        } else {
+            ctx.request_repaint_after(limit - idle);
+        }
+    }
+
+    fn render_locked(&mut self, ctx: &egui::Context) {
+        let panel_frame = egui::Frame::none().fill(egui::Color32::from_rgb(10, 10, 10)).inner_margin(20.0);
+        egui::CentralPanel::default().frame(panel_frame).show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(ui.available_height() / 3.0);
+                ui.label(egui::RichText::new("🔒").size(32.0));
+                ui.label(egui::RichText::new("PIN-BOARD IS LOCKED").size(10.0).strong().color(egui::Color32::from_gray(80)));
+                ui.add_space(12.0);
+                if ui.button("Unlock…").clicked() { self.unlock_prompt = true; }
+            });
+        });
+
+        if !self.unlock_prompt { return; }
+        let mut submit = false;
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("unlock"),
+            egui::ViewportBuilder::default()
+                .with_title("Unlock Pin-Board")
+                .with_inner_size([320.0, 140.0])
+                .with_resizable(false)
+                .with_always_on_top(),
+            |ctx, _| {
+                egui::CentralPanel::default().frame(egui::Frame::none().fill(egui::Color32::from_gray(20)).inner_margin(16.0)).show(ctx, |ui| {
+                    ui.label(egui::RichText::new("PASSPHRASE").size(10.0).strong().color(egui::Color32::from_gray(80)));
+                    let response = ui.add(egui::TextEdit::singleline(&mut *self.passphrase_input).password(true).desired_width(f32::INFINITY));
+                    if ui.memory(|m| m.focused().is_none()) { response.request_focus(); }
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) { submit = true; }
+
+                    if let Some(e) = &self.passphrase_error {
+                        ui.label(egui::RichText::new(e).size(11.0).color(egui::Color32::from_rgb(251, 113, 133)));
+                    }
+                    ui.add_space(6.0);
+                    if ui.button("Unlock").clicked() { submit = true; }
+                });
+                if ctx.input(|i| i.viewport().close_requested()) { self.unlock_prompt = false; }
+            },
+        );
+        if submit { self.unlock(ctx); }
+    }
+
     fn render_dashboard(&mut self, ctx: &egui::Context) {
         let panel_frame = egui::Frame::none()
             .fill(egui::Color32::from_rgb(10, 10, 10)) // Pure Dark
@@ -106,17 +845,28 @@ impl AppState {
                     });
                     ui.label(egui::RichText::new("NATIVE MINIMALIST WORKSPACE").size(10.0).strong().color(egui::Color32::from_gray(80)));
                 });
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+                    ui.menu_button(egui::RichText::new("BOARD").size(10.0).strong(), |ui| self.render_board_menu(ui));
+                });
             });
             
             ui.add_space(24.0);
 
+            self.render_notice(ui);
+
+            self.render_recovery_banner(ui);
+
+            self.render_tag_filter(ui);
+
             // Vibrant Stats
             ui.columns(3, |columns| {
-                let active = self.pins.iter().filter(|p| !p.is_completed).count();
-                let completed = self.pins.iter().filter(|p| p.is_completed).count();
+                let filter = self.tag_filter.as_deref();
+                let active = self.pins.iter().filter(|p| !p.is_completed && p.matches_tag(filter)).count();
+                let archived = self.archive.iter().filter(|p| p.matches_tag(filter)).count();
                 
                 self.stat_card(&mut columns[0], "PENDING", &active.to_string(), egui::Color32::from_rgb(52, 211, 153)); // Emerald
-                self.stat_card(&mut columns[1], "ARCHIVED", &completed.to_string(), egui::Color32::from_rgb(251, 113, 133)); // Rose
+                self.stat_card(&mut columns[1], "ARCHIVED", &archived.to_string(), egui::Color32::from_rgb(251, 113, 133)); // Rose
                 
                 let time = format!("{:02}:{:02}", self.global_timer.remaining_secs/60, self.global_timer.remaining_secs%60);
                 let timer_color = if self.global_timer.is_running { egui::Color32::from_rgb(251, 191, 36) } else { egui::Color32::from_gray(100) };
@@ -162,8 +912,8 @@ impl AppState {
                         ui.label(egui::RichText::new("Timer:").size(11.0).color(egui::Color32::from_gray(120)));
                         ui.add(egui::DragValue::new(&mut self.new_pin_minutes).suffix("m").range(0..=999).speed(1.0));
                         
-                        if self.new_pin_minutes > 0 {
-                             if ui.small_button("Clear").clicked() { self.new_pin_minutes = 0; }
+                        if self.new_pin_minutes > 0 && ui.small_button("Clear").clicked() {
+                            self.new_pin_minutes = 0;
                         }
 
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -178,17 +928,24 @@ impl AppState {
                                     Some(Local::now() + chrono::Duration::minutes(self.new_pin_minutes as i64))
                                 } else { None };
                                 
-                                let trimmed = self.new_pin_content.trim();
-                                let (title, body) = if let Some((first, rest)) = trimmed.split_once('\n') {
-                                    (first.to_string(), rest.trim().to_string())
-                                } else {
-                                    (trimmed.to_string(), String::new())
-                                };
-                                
-                                self.pins.push(Pin::new(title, body, deadline));
+                                let (title, body) = pin::split_text(&self.new_pin_content);
+                                let mut pin = Pin::new(title, body, deadline);
+                                // Hand-written `[ ]` lists become real checklists.
+                                if pin::looks_like_checklist(&pin.content) { pin.make_checklist(); }
+                                self.pins.push(pin);
+                                self.edited(Edit::Step);
                                 self.new_pin_content.clear();
                                 self.new_pin_minutes = 0;
                             }
+
+                            if ui.small_button("Paste image").on_hover_text("Pin the image on the clipboard").clicked() {
+                                self.pin_image(images::paste());
+                            }
+                            if ui.small_button("Image…").on_hover_text("Pin an image file. You can also drop files here.").clicked() {
+                                if let Some(path) = images::choose() {
+                                    self.pin_image(images::import(&path));
+                                }
+                            }
                         });
                     });
                 });
@@ -210,13 +967,26 @@ impl AppState {
 
                 if ui.add(egui::Button::new(egui::RichText::new("START").size(10.0).strong()).rounding(4.0)).clicked() {
                     self.global_timer.start(minutes);
+                    self.changed();
                 }
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     ui.spacing_mut().item_spacing.x = 6.0;
-                    if ui.small_button("CLEAN").clicked() { self.pins.retain(|p| !p.is_completed); }
-                    if ui.small_button("HIDE ALL").clicked() { for p in &mut self.pins { p.visible = false; } }
-                    if ui.small_button("SHOW ALL").clicked() { for p in &mut self.pins { p.visible = true; } }
+                    if ui.small_button("CLEAN").clicked() {
+                        let (done, pending): (Vec<Pin>, Vec<Pin>) = std::mem::take(&mut self.pins).into_iter().partition(|p| p.is_completed);
+                        self.pins = pending;
+                        self.archive.extend(done);
+                        self.edited(Edit::Step);
+                    }
+                    // With a tag filter, only the pins listed.
+                    let filter = self.tag_filter.clone();
+                    let mut show = None;
+                    if ui.small_button("HIDE ALL").clicked() { show = Some(false); }
+                    if ui.small_button("SHOW ALL").clicked() { show = Some(true); }
+                    if let Some(visible) = show {
+                        for p in self.pins.iter_mut().filter(|p| p.matches_tag(filter.as_deref())) { p.visible = visible; }
+                        self.edited(Edit::Step);
+                    }
                 });
             });
 
@@ -224,82 +994,451 @@ impl AppState {
             ui.separator();
             ui.add_space(12.0);
 
-            // Dashboard List
-            egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
-                 if self.pins.is_empty() {
-                     ui.add_space(40.0);
-                     ui.vertical_centered(|ui| {
-                         ui.label(egui::RichText::new("Your workspace is empty").color(egui::Color32::from_gray(60)));
-                     });
-                 }
+            // View Tabs
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 12.0;
+                let trash_label = if self.trash.is_empty() { "TRASH".to_string() } else { format!("TRASH ({})", self.trash.len()) };
+                let tabs = [
+                    (DashboardView::Pins, "PINS".to_string()),
+                    (DashboardView::Archive, "ARCHIVE".to_string()),
+                    (DashboardView::Trash, trash_label),
+                    (DashboardView::Settings, "SETTINGS".to_string()),
+                ];
+                for (view, label) in tabs {
+                    let selected = self.view == view;
+                    let text = egui::RichText::new(label).size(10.0).strong()
+                        .color(if selected { egui::Color32::from_rgb(80, 160, 255) } else { egui::Color32::from_gray(80) });
+                    if ui.add(egui::Label::new(text).sense(egui::Sense::click())).clicked() { self.view = view; }
+                }
+            });
 
-                 let mut action = None;
+            ui.add_space(12.0);
 
-                 for (i, pin) in self.pins.iter().enumerate() {
-                    if i > 0 { ui.add_space(4.0); }
-                    
-                    let mut toggle_viz = false;
-                    let mut delete = false;
-
-                    egui::Frame::none()
-                        .fill(egui::Color32::from_gray(18))
-                        .inner_margin(12.0)
-                        .rounding(10.0)
-                        .stroke(egui::Stroke::new(1.0, egui::Color32::from_gray(30)))
-                        .show(ui, |ui| {
-                            ui.horizontal(|ui| {
-                                 let preview = pin.content.lines().next().unwrap_or("").chars().take(22).collect::<String>();
-                                 // Simple text indicators instead of emoji
-                                 let status_txt = if pin.is_completed { "(Done)" } else { "•" };
-                                 ui.label(egui::RichText::new(status_txt).size(10.0).color(if pin.is_completed { egui::Color32::from_gray(100) } else { egui::Color32::from_rgb(80, 160, 255) }));
-                                 ui.label(egui::RichText::new(preview).color(if pin.is_completed { egui::Color32::from_gray(60) } else { egui::Color32::from_gray(200) }));
+            match self.view {
+                DashboardView::Pins => self.render_pin_list(ui),
+                DashboardView::Archive => self.render_archive(ui),
+                DashboardView::Trash => self.render_trash(ui),
+                DashboardView::Settings => self.render_settings(ui),
+            }
+        });
+    }
+
+    fn render_tag_filter(&mut self, ui: &mut egui::Ui) {
+        let mut tags: Vec<&str> = self.pins.iter().chain(self.archive.iter()).flat_map(|p| p.tags.iter().map(String::as_str)).collect();
+        tags.sort_by_key(|t| t.to_lowercase());
+        tags.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+        // The last pin with the filtered tag lost it.
+        if self.tag_filter.as_ref().is_some_and(|f| !tags.iter().any(|t| t.eq_ignore_ascii_case(f))) {
+            self.tag_filter = None;
+        }
+        if tags.is_empty() { return; }
+
+        let mut selected = self.tag_filter.clone();
+        ui.horizontal_wrapped(|ui| {
+            ui.label(egui::RichText::new("TAGS").size(10.0).strong().color(egui::Color32::from_gray(60)));
+            if ui.selectable_label(selected.is_none(), egui::RichText::new("All").size(11.0)).clicked() { selected = None; }
+            for tag in tags {
+                let active = selected.as_ref().is_some_and(|s| s.eq_ignore_ascii_case(tag));
+                if ui.selectable_label(active, egui::RichText::new(format!("#{tag}")).size(11.0)).clicked() {
+                    selected = if active { None } else { Some(tag.to_string()) };
+                }
+            }
+        });
+        self.tag_filter = selected;
+        ui.add_space(12.0);
+    }
+
+    fn render_pin_list(&mut self, ui: &mut egui::Ui) {
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+             let filter = self.tag_filter.as_deref();
+             if self.pins.is_empty() {
+                 ui.add_space(40.0);
+                 ui.vertical_centered(|ui| {
+                     ui.label(egui::RichText::new("Your workspace is empty").color(egui::Color32::from_gray(60)));
+                 });
+             } else if let Some(tag) = filter.filter(|_| !self.pins.iter().any(|p| p.matches_tag(filter))) {
+                 ui.add_space(40.0);
+                 ui.vertical_centered(|ui| {
+                     ui.label(egui::RichText::new(format!("No pins tagged #{tag}")).color(egui::Color32::from_gray(60)));
+                 });
+             }
+
+             let mut action = None;
+
+             for (n, (i, pin)) in self.pins.iter().enumerate().filter(|(_, p)| p.matches_tag(filter)).enumerate() {
+                if n > 0 { ui.add_space(4.0); }
+                
+                let mut toggle_viz = false;
+                let mut delete = false;
+
+                egui::Frame::none()
+                    .fill(egui::Color32::from_gray(18))
+                    .inner_margin(12.0)
+                    .rounding(10.0)
+                    .stroke(egui::Stroke::new(1.0, egui::Color32::from_gray(30)))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                             let mut preview = pin.body_text().lines().next().unwrap_or("").chars().take(22).collect::<String>();
+                             if preview.is_empty() && pin.image.is_some() { preview = "[image]".to_string(); }
+                             // Simple text indicators instead of emoji
+                             let (rect, _) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+                             ui.painter().circle_filled(rect.center(), 4.0, self.palette.color(pin.color_idx));
+                             ui.interact(rect, ui.id().with(("color", &pin.id)), egui::Sense::hover()).on_hover_text(self.palette.describe(pin.color_idx));
+                             let status_txt = if pin.is_completed { "(Done)" } else { "•" };
+                             ui.label(egui::RichText::new(status_txt).size(10.0).color(if pin.is_completed { egui::Color32::from_gray(100) } else { egui::Color32::from_rgb(80, 160, 255) }));
+                             ui.label(egui::RichText::new(preview).color(if pin.is_completed { egui::Color32::from_gray(60) } else { egui::Color32::from_gray(200) }));
+                             for tag in &pin.tags {
+                                 ui.label(egui::RichText::new(format!("#{tag}")).size(10.0).color(egui::Color32::from_gray(90)));
+                             }
+                             
+                             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                 ui.style_mut().spacing.item_spacing.x = 10.0;
                                  
-                                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                     ui.style_mut().spacing.item_spacing.x = 10.0;
-                                     
-                                     // Painted Close Icon
-                                     let (rect, resp) = ui.allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::click());
-                                     let painter = ui.painter();
-                                     let c = rect.center();
-                                     let r = 3.5;
-                                     let color = if resp.hovered() { egui::Color32::RED } else { egui::Color32::GRAY };
-                                     painter.line_segment([c + egui::vec2(-r, -r), c + egui::vec2(r, r)], egui::Stroke::new(1.5, color));
-                                     painter.line_segment([c + egui::vec2(-r, r), c + egui::vec2(r, -r)], egui::Stroke::new(1.5, color));
-                                     if resp.clicked() { delete = true; }
-
-                                     // Painted Eye Icon
-                                     let (rect, resp) = ui.allocate_exact_size(egui::vec2(16.0, 14.0), egui::Sense::click());
-                                     let painter = ui.painter();
-                                     let c = rect.center();
-                                     let color = if resp.hovered() { egui::Color32::WHITE } else { egui::Color32::GRAY };
-                                     
-                                     if pin.visible {
-                                         // Eye Open
-                                         painter.circle_stroke(c, 4.0, egui::Stroke::new(1.5, color));
-                                         painter.circle_filled(c, 1.5, color);
-                                     } else {
-                                         // Eye Closed (Strike)
-                                         painter.circle_stroke(c, 4.0, egui::Stroke::new(1.0, color.gamma_multiply(0.5)));
-                                         painter.line_segment([c + egui::vec2(-4.0, 4.0), c + egui::vec2(4.0, -4.0)], egui::Stroke::new(1.5, color));
-                                     }
-                                     if resp.clicked() { toggle_viz = true; }
-                                 });
-                            });
+                                 // Painted Close Icon
+                                 let (rect, resp) = ui.allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::click());
+                                 let painter = ui.painter();
+                                 let c = rect.center();
+                                 let r = 3.5;
+                                 let color = if resp.hovered() { egui::Color32::RED } else { egui::Color32::GRAY };
+                                 painter.line_segment([c + egui::vec2(-r, -r), c + egui::vec2(r, r)], egui::Stroke::new(1.5, color));
+                                 painter.line_segment([c + egui::vec2(-r, r), c + egui::vec2(r, -r)], egui::Stroke::new(1.5, color));
+                                 if resp.clicked() { delete = true; }
+
+                                 // Painted Eye Icon
+                                 let (rect, resp) = ui.allocate_exact_size(egui::vec2(16.0, 14.0), egui::Sense::click());
+                                 let painter = ui.painter();
+                                 let c = rect.center();
+                                 let color = if resp.hovered() { egui::Color32::WHITE } else { egui::Color32::GRAY };
+                                 
+                                 if pin.visible {
+                                     // Eye Open
+                                     painter.circle_stroke(c, 4.0, egui::Stroke::new(1.5, color));
+                                     painter.circle_filled(c, 1.5, color);
+                                 } else {
+                                     // Eye Closed (Strike)
+                                     painter.circle_stroke(c, 4.0, egui::Stroke::new(1.0, color.gamma_multiply(0.5)));
+                                     painter.line_segment([c + egui::vec2(-4.0, 4.0), c + egui::vec2(4.0, -4.0)], egui::Stroke::new(1.5, color));
+                                 }
+                                 if resp.clicked() { toggle_viz = true; }
+                             });
                         });
-                    
-                    if toggle_viz { action = Some(("toggle", i)); }
-                    if delete { action = Some(("delete", i)); }
-                 }
+                    });
+                
+                if toggle_viz { action = Some(("toggle", i)); }
+                if delete { action = Some(("delete", i)); }
+             }
 
-                 if let Some((act, idx)) = action {
-                     match act {
-                         "toggle" => self.pins[idx].visible = !self.pins[idx].visible,
-                         "delete" => { self.pins.remove(idx); },
-                         _ => {}
-                     }
+             if let Some((act, idx)) = action {
+                 match act {
+                     "toggle" => self.pins[idx].visible = !self.pins[idx].visible,
+                     "delete" => self.trash_pin(idx),
+                     _ => {}
                  }
+                 self.edited(Edit::Step);
+             }
+        });
+    }
+
+    fn trash_pin(&mut self, idx: usize) {
+        let pin = self.pins.remove(idx);
+        self.trash.push(TrashedPin::new(pin));
+    }
+
+    fn render_archive(&mut self, ui: &mut egui::Ui) {
+        ui.add(egui::TextEdit::singleline(&mut self.archive_query)
+            .hint_text("Search archive...")
+            .desired_width(f32::INFINITY)
+            .margin(egui::vec2(6.0, 4.0)));
+
+        ui.add_space(12.0);
+
+        let query = self.archive_query.trim().to_lowercase();
+        let mut matches: Vec<usize> = (0..self.archive.len())
+            .filter(|&i| {
+                let pin = &self.archive[i];
+                pin.matches_tag(self.tag_filter.as_deref())
+                    && (query.is_empty() || pin.title.to_lowercase().contains(&query) || pin.body_text().to_lowercase().contains(&query))
+            })
+            .collect();
+        // Most recently completed first
+        matches.sort_by_key(|&i| std::cmp::Reverse(self.archive[i].completed_at));
+
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+            if matches.is_empty() {
+                ui.add_space(40.0);
+                ui.vertical_centered(|ui| {
+                    let msg = if self.archive.is_empty() { "Nothing archived yet" } else { "No archived pins match" };
+                    ui.label(egui::RichText::new(msg).color(egui::Color32::from_gray(60)));
+                });
+            }
+
+            let mut restore = None;
+            let mut purge = None;
+            let now = chrono::Utc::now();
+
+            for i in matches {
+                let pin = &self.archive[i];
+                egui::Frame::none()
+                    .fill(egui::Color32::from_gray(18))
+                    .inner_margin(12.0)
+                    .rounding(10.0)
+                    .stroke(egui::Stroke::new(1.0, egui::Color32::from_gray(30)))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            let text = if pin.title.is_empty() { &pin.content } else { &pin.title };
+                            let preview = text.lines().next().unwrap_or("").chars().take(22).collect::<String>();
+                            ui.label(egui::RichText::new("✓").size(10.0).color(egui::Color32::from_rgb(52, 211, 153)));
+                            ui.label(egui::RichText::new(preview).color(egui::Color32::from_gray(160)));
+
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.style_mut().spacing.item_spacing.x = 8.0;
+                                if ui.small_button("Delete").on_hover_text("Delete forever").clicked() { purge = Some(i); }
+                                if ui.small_button("Restore").clicked() { restore = Some(i); }
+
+                                if let Some(done) = pin.completed_at {
+                                    ui.label(egui::RichText::new(format!("done {}", timefmt::relative(done, now))).size(9.0).color(egui::Color32::from_gray(80)))
+                                        .on_hover_text(timefmt::absolute(done, self.settings.clock_24h));
+                                }
+                            });
+                        });
+                    });
+                ui.add_space(4.0);
+            }
+
+            if let Some(i) = restore {
+                let mut pin = self.archive.remove(i);
+                pin.set_completed(false);
+                pin.visible = true;
+                self.pins.push(pin);
+            }
+            if let Some(i) = purge { self.archive.remove(i); }
+            if restore.is_some() || purge.is_some() { self.edited(Edit::Step); }
+        });
+    }
+
+    fn render_trash(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("PURGE AFTER:").size(10.0).strong().color(egui::Color32::from_gray(60)));
+            if ui.add(egui::DragValue::new(&mut self.settings.trash_retention_days).suffix(" days").range(1..=365)).changed() {
+                self.changed();
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.add_enabled(!self.trash.is_empty(), egui::Button::new(egui::RichText::new("EMPTY").size(10.0).strong()).small()).clicked() {
+                    self.trash.clear();
+                    self.edited(Edit::Step);
+                }
             });
         });
+
+        ui.add_space(12.0);
+
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+            if self.trash.is_empty() {
+                ui.add_space(40.0);
+                ui.vertical_centered(|ui| {
+                    ui.label(egui::RichText::new("Trash is empty").color(egui::Color32::from_gray(60)));
+                });
+            }
+
+            let mut restore = None;
+            let mut purge = None;
+            let now = chrono::Utc::now();
+
+            // Most recently deleted first
+            for (i, item) in self.trash.iter().enumerate().rev() {
+                egui::Frame::none()
+                    .fill(egui::Color32::from_gray(18))
+                    .inner_margin(12.0)
+                    .rounding(10.0)
+                    .stroke(egui::Stroke::new(1.0, egui::Color32::from_gray(30)))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            let pin = &item.pin;
+                            let text = if pin.title.is_empty() { &pin.content } else { &pin.title };
+                            let preview = text.lines().next().unwrap_or("").chars().take(22).collect::<String>();
+                            ui.label(egui::RichText::new(preview).color(egui::Color32::from_gray(160)));
+
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.style_mut().spacing.item_spacing.x = 8.0;
+                                if ui.small_button("Delete").on_hover_text("Delete forever").clicked() { purge = Some(i); }
+                                if ui.small_button("Restore").clicked() { restore = Some(i); }
+
+                                ui.label(egui::RichText::new(format!("deleted {}", timefmt::relative(item.deleted_at, now))).size(9.0).color(egui::Color32::from_gray(80)))
+                                    .on_hover_text(timefmt::absolute(item.deleted_at, self.settings.clock_24h));
+                            });
+                        });
+                    });
+                ui.add_space(4.0);
+            }
+
+            if let Some(i) = restore {
+                let mut pin = self.trash.remove(i).pin;
+                pin.visible = true;
+                self.pins.push(pin);
+            }
+            if let Some(i) = purge { self.trash.remove(i); }
+            if restore.is_some() || purge.is_some() { self.edited(Edit::Step); }
+        });
+    }
+
+    fn render_settings(&mut self, ui: &mut egui::Ui) {
+        let before = self.settings.clone();
+        egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+            ui.label(egui::RichText::new("CLOCK").size(10.0).strong().color(egui::Color32::from_gray(60)));
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.settings.clock_24h, false, "12-hour");
+                ui.selectable_value(&mut self.settings.clock_24h, true, "24-hour");
+            });
+
+            ui.add_space(16.0);
+            ui.label(egui::RichText::new("TODO.TXT MIRROR").size(10.0).strong().color(egui::Color32::from_gray(60)));
+            match &self.todo_mirror {
+                Some(mirror) => {
+                    ui.label(egui::RichText::new(mirror.path().display().to_string()).size(11.0).color(egui::Color32::from_gray(160)));
+                    if ui.button("Stop mirroring").clicked() {
+                        self.todo_mirror = None;
+                        self.settings.todo_txt_mirror = None;
+                    }
+                }
+                None => {
+                    ui.label(egui::RichText::new("Keep a todo.txt file in step with the board while Pin-Board runs.").size(11.0).color(egui::Color32::from_gray(100)));
+                    if ui.button("Choose file…").clicked() {
+                        let path = rfd::FileDialog::new().add_filter("todo.txt", &["txt"]).set_file_name("todo.txt").save_file();
+                        if let Some(path) = path {
+                            self.todo_mirror = Some(todotxt::Mirror::new(path.clone()));
+                            self.settings.todo_txt_mirror = Some(path);
+                        }
+                    }
+                }
+            }
+
+            ui.add_space(16.0);
+            ui.label(egui::RichText::new("MARKDOWN FOLDER").size(10.0).strong().color(egui::Color32::from_gray(60)));
+            match &self.folder_sync {
+                Some(sync) => {
+                    ui.label(egui::RichText::new(sync.dir().display().to_string()).size(11.0).color(egui::Color32::from_gray(160)));
+                    if ui.button("Stop syncing").clicked() {
+                        self.folder_sync = None;
+                        self.settings.markdown_folder = None;
+                    }
+                }
+                None => {
+                    ui.label(egui::RichText::new("Keep one Markdown file per pin in a folder, for editors, grep and git.").size(11.0).color(egui::Color32::from_gray(100)));
+                    if ui.button("Choose folder…").clicked() {
+                        if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                            self.start_folder_sync(dir.clone(), ui.ctx());
+                            if self.folder_sync.is_some() { self.settings.markdown_folder = Some(dir); }
+                        }
+                    }
+                }
+            }
+
+            ui.add_space(16.0);
+            self.render_palette_settings(ui);
+
+            ui.add_space(16.0);
+            ui.label(egui::RichText::new("ENCRYPTION").size(10.0).strong().color(egui::Color32::from_gray(60)));
+            let encrypted = self.key.is_some();
+            if encrypted {
+                ui.label(egui::RichText::new("The saved board is encrypted with your passphrase.").size(11.0).color(egui::Color32::from_gray(160)));
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("Lock after").size(11.0).color(egui::Color32::from_gray(120)));
+                    ui.add(egui::DragValue::new(&mut self.settings.auto_lock_minutes).range(0..=240).suffix(" min"));
+                    ui.label(egui::RichText::new("idle (0 = never)").size(11.0).color(egui::Color32::from_gray(120)));
+                });
+            } else {
+                ui.label(egui::RichText::new("Encrypt the saved board with a passphrase. Forget it and the board is gone for good.").size(11.0).color(egui::Color32::from_gray(100)));
+            }
+
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut *self.passphrase_input).password(true).hint_text(if encrypted { "New passphrase" } else { "Passphrase" }).desired_width(140.0));
+                ui.add(egui::TextEdit::singleline(&mut *self.passphrase_confirm).password(true).hint_text("Confirm").desired_width(140.0));
+                if ui.button(if encrypted { "Change passphrase" } else { "Encrypt board" }).clicked() { self.set_passphrase(); }
+            });
+            if let Some(e) = &self.passphrase_error {
+                ui.label(egui::RichText::new(e).size(11.0).color(egui::Color32::from_rgb(251, 113, 133)));
+            }
+
+            if encrypted {
+                ui.horizontal(|ui| {
+                    if ui.button("Remove encryption").clicked() {
+                        self.key = None;
+                        self.persist();
+                        self.notice = Some("Board saved without encryption.".to_string());
+                    }
+                    let can_lock = self.load_error.is_none();
+                    let lock = ui.add_enabled(can_lock, egui::Button::new("Lock now"))
+                        .on_disabled_hover_text("Saving is paused, so locking would throw this board away.");
+                    if lock.clicked() { self.lock(); }
+                });
+                if self.load_error.is_some() {
+                    ui.label(egui::RichText::new("Locking, and locking when idle, is off while saving is paused.").size(11.0).color(egui::Color32::from_rgb(251, 191, 36)));
+                }
+            }
+        });
+        if !self.locked && self.settings != before { self.changed(); }
+    }
+
+    fn render_palette_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("PALETTE").size(10.0).strong().color(egui::Color32::from_gray(60)));
+            ui.menu_button(egui::RichText::new("Presets").size(11.0), |ui| {
+                let presets = palette::builtin().into_iter().chain(self.palette.presets.clone());
+                for preset in presets.filter(|p| !p.colors.is_empty()) {
+                    if ui.button(&preset.name).clicked() {
+                        self.palette.colors = preset.colors;
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+        ui.label(egui::RichText::new("Name the colors after what you use them for. Pins keep their color when you reorder or remove others.").size(11.0).color(egui::Color32::from_gray(100)));
+
+        let before = self.palette.clone();
+        let (mut swap, mut remove) = (None, None);
+        let count = self.palette.colors.len();
+        for (i, color) in self.palette.colors.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.color_edit_button_srgb(&mut color.rgb);
+                ui.add(egui::TextEdit::singleline(&mut color.name).hint_text("Name").desired_width(90.0));
+                ui.add(egui::TextEdit::singleline(&mut color.meaning).hint_text("Meaning, e.g. Meetings").desired_width(160.0));
+                if ui.add_enabled(i > 0, egui::Button::new("▲").small()).clicked() { swap = Some((i - 1, i)); }
+                if ui.add_enabled(i + 1 < count, egui::Button::new("▼").small()).clicked() { swap = Some((i, i + 1)); }
+                if ui.add_enabled(count > 1, egui::Button::new("×").small()).clicked() { remove = Some(i); }
+            });
+        }
+        if let Some((a, b)) = swap {
+            self.palette.colors.swap(a, b);
+            self.remap_colors(|i| if i == a { b } else if i == b { a } else { i });
+        }
+        if let Some(r) = remove {
+            self.palette.colors.remove(r);
+            self.remap_colors(|i| if i == r { 0 } else if i > r { i - 1 } else { i });
+        }
+        if ui.small_button("+ Add color").clicked() {
+            self.palette.colors.push(PaletteColor { name: format!("Color {}", count + 1), rgb: [230, 230, 230], meaning: String::new() });
+        }
+        if let Some(path) = Palette::path() {
+            ui.label(egui::RichText::new(format!("Saved in {}", path.display())).size(10.0).color(egui::Color32::from_gray(80)));
+        }
+
+        if self.palette != before {
+            palette::set_current(self.palette.clone());
+            self.palette_dirty = true;
+            self.changed();
+        }
+    }
+
+    /// Follows a palette change through every pin's `color_idx`, undo
+    /// history included. The palette itself is not undone, so this keeps
+    /// every pin the color it was after an undo.
+    fn remap_colors(&mut self, map: impl Fn(usize) -> usize) {
+        let pins = self.pins.iter_mut().chain(self.archive.iter_mut()).chain(self.trash.iter_mut().map(|t| &mut t.pin));
+        for pin in pins {
+            pin.color_idx = map(pin.color_idx);
+        }
+        self.history.remap_colors(map);
     }
 
     fn stat_card(&self, ui: &mut egui::Ui, label: &str, value: &str, color: egui::Color32) {
@@ -317,19 +1456,59 @@ impl AppState {
             });
     }
 
-    fn render_pins(&mut self, ctx: &egui::Context) {
+    fn render_pins(&mut self, ctx: &egui::Context) -> Option<HistoryAction> {
         let mut to_delete = Vec::new();
         let mut to_clone = Vec::new();
+        let mut history = None;
         
+        let (mut edit, mut moved) = (None, false);
         for (idx, pin) in self.pins.iter_mut().enumerate() {
-             let (deleted, cloned) = pin.render(ctx);
-             if deleted { to_delete.push(idx); }
-             if let Some(c) = cloned { to_clone.push(c); }
+             let response = pin.render(ctx, &self.settings, &self.palette);
+             if response.active { self.last_activity = Some(Instant::now()); }
+             // Pins edited together in one frame undo together.
+             edit = match (edit, response.edit) {
+                 (None, e) | (e, None) => e,
+                 _ => Some(Edit::Step),
+             };
+             moved |= response.moved;
+             if response.delete { to_delete.push(idx); }
+             if let Some(c) = response.clone { to_clone.push(c); }
+             history = history.or(response.history);
         }
         
+        if !to_delete.is_empty() || !to_clone.is_empty() { edit = Some(Edit::Step); }
         to_delete.sort_by(|a, b| b.cmp(a));
-        for idx in to_delete { self.pins.remove(idx); }
+        for idx in to_delete { self.trash_pin(idx); }
         for pin in to_clone { self.pins.push(pin); }
+        match edit {
+            Some(edit) => self.edited(edit),
+            None if moved => self.changed(),
+            None => {}
+        }
+        history
+    }
+
+    fn apply_history(&mut self, action: HistoryAction) {
+        let snapshot = match action {
+            HistoryAction::Undo => self.history.undo(),
+            HistoryAction::Redo => self.history.redo(),
+        };
+        let Some(snapshot) = snapshot else { return };
+
+        // Snapshots carry no window geometry; keep whatever each pin has now.
+        let mut layout: std::collections::HashMap<String, Layout> = self.trash.iter().map(|t| &t.pin)
+            .chain(self.archive.iter())
+            .chain(self.pins.iter())
+            .map(|p| (p.id.clone(), p.layout()))
+            .collect();
+        let mut restore = |mut pin: Pin| {
+            if let Some(l) = layout.remove(&pin.id) { pin.set_layout(l); }
+            pin
+        };
+        self.pins = snapshot.pins.into_iter().map(&mut restore).collect();
+        self.archive = snapshot.archive.into_iter().map(&mut restore).collect();
+        self.trash = snapshot.trash.into_iter().map(|t| TrashedPin { pin: restore(t.pin), ..t }).collect();
+        self.changed();
     }
 
     fn render_global_timer(&mut self, ctx: &egui::Context) {
@@ -366,15 +1545,17 @@ impl AppState {
                              let btn_size = egui::vec2(24.0, 24.0);
                              
                              if self.global_timer.is_running {
-                                 if ui.add(egui::Button::new("⏸").min_size(btn_size).frame(false)).clicked() { self.global_timer.is_running = false; }
+                                 if ui.add(egui::Button::new("⏸").min_size(btn_size).frame(false)).clicked() { self.global_timer.is_running = false; self.changed(); }
                              } else {
                                  if ui.add(egui::Button::new("▶").min_size(btn_size).frame(false)).clicked() { 
                                      self.global_timer.is_running = true; 
+                                     self.changed();
                                  }
                              }
                              
                              if ui.add(egui::Button::new("⏹").min_size(btn_size).frame(false)).clicked() { 
                                  self.global_timer.stop();
+                                 self.changed();
                              }
                          });
                      });
@@ -386,18 +1567,56 @@ impl AppState {
 
 impl eframe::App for AppState {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-         if let Ok(value) = serde_json::to_string(self) {
-             storage.set_string("pinboard_state", value);
-         }
+        self.persist();
+        // eframe keeps its own copy of app.ron and would write an old
+        // plain-text board back over `store.scrub()`.
+        if self.key.is_some() {
+            storage.set_string(store::LEGACY_KEY, String::new());
+        }
     }
 
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        if self.global_timer.is_running || self.pins.iter().any(|p| p.deadline.is_some() && !p.is_completed) {
+        if self.shutdown.requested() {
+            // Pins are not drawn this frame, so their viewports go away without
+            // seeing a close request (which would trash them).
+            self.persist();
+            ctx.send_viewport_cmd_to(egui::ViewportId::ROOT, egui::ViewportCommand::Close);
+            return;
+        }
+
+        let incoming: Vec<Incoming> = self.requests.as_ref().map(|r| r.try_iter().collect()).unwrap_or_default();
+        for incoming in incoming {
+            incoming.answer(|request| self.handle_request(ctx, request));
+        }
+
+        if self.locked {
+            self.render_locked(ctx);
+            return;
+        }
+        if ctx.input(|i| !i.events.is_empty()) { self.last_activity = Some(Instant::now()); }
+
+        if self.global_timer.is_running || self.todo_mirror.is_some() || self.pins.iter().any(|p| p.deadline.is_some() && !p.is_completed) {
             ctx.request_repaint_after(Duration::from_secs(1));
         }
-        self.global_timer.update();
+        if self.global_timer.update() { self.changed(); }
+        self.sync_todo_txt();
+        self.sync_folder();
+        if trash::purge_expired(&mut self.trash, self.settings.trash_retention_days) { self.edited(Edit::Step); }
+
+        let mut history = HistoryAction::from_shortcuts(ctx);
+        self.pin_dropped_images(ctx);
         self.render_dashboard(ctx);
+        // "Lock now" in the settings.
+        if self.locked { return; }
+        self.render_import_preview(ctx);
         self.render_global_timer(ctx);
-        self.render_pins(ctx);
+        history = history.or(self.render_pins(ctx));
+
+        if let Some(action) = history {
+            self.apply_history(action);
+            ctx.request_repaint();
+        }
+        self.autosave(ctx);
+        self.auto_lock(ctx);
     }
 }
@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+// Save once changes pause for this long...
+const DEBOUNCE: Duration = Duration::from_millis(300);
+// ...but never hold unsaved changes for longer than this while typing.
+const MAX_WAIT: Duration = Duration::from_secs(1);
+
+/// Decides when the board has changed enough to write it out. Whatever
+/// changes the board calls `mark`; nothing is serialized until `due`.
+#[derive(Default)]
+pub struct Autosave {
+    dirty_since: Option<Instant>,
+    last_change: Option<Instant>,
+}
+
+impl Autosave {
+    pub fn mark(&mut self) {
+        self.mark_at(Instant::now());
+    }
+
+    fn mark_at(&mut self, now: Instant) {
+        self.last_change = Some(now);
+        self.dirty_since.get_or_insert(now);
+    }
+
+    /// Whether there are unsaved changes that should be written now.
+    pub fn due(&self) -> bool {
+        self.due_at(Instant::now())
+    }
+
+    fn due_at(&self, now: Instant) -> bool {
+        let Some(since) = self.dirty_since else { return false };
+        let quiet = self.last_change.is_some_and(|t| now - t >= DEBOUNCE);
+        quiet || now - since >= MAX_WAIT
+    }
+
+    /// How long until unsaved changes are due, if there are any.
+    pub fn wait(&self) -> Option<Duration> {
+        let since = self.dirty_since?;
+        let quiet = self.last_change.map_or(Duration::ZERO, |t| DEBOUNCE.saturating_sub(t.elapsed()));
+        Some(quiet.min(MAX_WAIT.saturating_sub(since.elapsed())))
+    }
+
+    /// Called once the board was written, by `due` or some other way.
+    pub fn saved(&mut self) {
+        self.dirty_since = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_once_changes_pause() {
+        let start = Instant::now();
+        let mut autosave = Autosave::default();
+        assert!(!autosave.due_at(start + MAX_WAIT));
+
+        autosave.mark_at(start);
+        assert!(!autosave.due_at(start + DEBOUNCE / 2));
+        assert!(autosave.due_at(start + DEBOUNCE));
+        autosave.saved();
+        assert!(!autosave.due_at(start + MAX_WAIT));
+    }
+
+    #[test]
+    fn saves_during_non_stop_typing() {
+        let start = Instant::now();
+        let mut autosave = Autosave::default();
+        let step = DEBOUNCE / 3;
+        let mut now = start;
+        while now - start < MAX_WAIT {
+            autosave.mark_at(now);
+            assert!(!autosave.due_at(now));
+            now += step;
+        }
+        assert!(autosave.due_at(now));
+    }
+}
@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+use crate::images;
+use crate::migrate::{self, SCHEMA_VERSION};
+use crate::pin::Pin;
+
+const FORMAT: &str = "pin-board-bundle";
+const BUNDLE_VERSION: u64 = 1;
+
+/// A set of pins handed from one board to another (see docs/IMPORT_EXPORT.md).
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    format: String,
+    version: u64,
+    // Lets an older bundle's pins go through the same migrations as a stored board.
+    schema_version: u64,
+    exported_at: DateTime<Utc>,
+    pins: Vec<Value>,
+}
+
+pub fn export(pins: &[Pin]) -> serde_json::Result<String> {
+    let bundle = Bundle {
+        format: FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        schema_version: SCHEMA_VERSION,
+        exported_at: Utc::now(),
+        pins: pins.iter().map(serde_json::to_value).collect::<Result<_, _>>()?,
+    };
+    serde_json::to_string_pretty(&bundle)
+}
+
+pub fn parse(text: &str) -> Result<Vec<Pin>, String> {
+    let bundle: Bundle = serde_json::from_str(text).map_err(|e| format!("not a Pin-Board bundle ({e})"))?;
+    if bundle.format != FORMAT {
+        return Err(format!("unknown bundle format \"{}\"", bundle.format));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!("bundle version {} is newer than this build understands", bundle.version));
+    }
+
+    let mut doc = json!({ "schema_version": bundle.schema_version, "pins": bundle.pins });
+    migrate::migrate(&mut doc).map_err(|e| e.to_string())?;
+    let mut pins: Vec<Pin> = serde_json::from_value(doc["pins"].take()).map_err(|e| format!("bad pin in bundle ({e})"))?;
+    for pin in &mut pins {
+        pin.image = pin.image.take().filter(|name| images::is_plain_name(name));
+    }
+    Ok(pins)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ImportAction {
+    Add,
+    Update,
+    Skip,
+}
+
+/// What an import would do, shown for confirmation before anything changes.
+pub struct ImportPlan {
+    pub source: PathBuf,
+    pub entries: Vec<(Pin, ImportAction)>,
+}
+
+impl ImportPlan {
+    /// New ids are added; known ids are updated only if the incoming copy was
+    /// modified more recently than ours.
+    pub fn new<'a>(source: PathBuf, existing: impl Iterator<Item = &'a Pin> + Clone, incoming: Vec<Pin>) -> Self {
+        let entries = incoming
+            .into_iter()
+            .map(|pin| {
+                let action = match existing.clone().find(|p| p.id == pin.id) {
+                    None => ImportAction::Add,
+                    Some(ours) if pin.modified_at > ours.modified_at => ImportAction::Update,
+                    Some(_) => ImportAction::Skip,
+                };
+                (pin, action)
+            })
+            .collect();
+        Self { source, entries }
+    }
+
+    pub fn count(&self, action: ImportAction) -> usize {
+        self.entries.iter().filter(|(_, a)| *a == action).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn pin(title: &str) -> Pin {
+        Pin::new(title.to_string(), String::new(), None)
+    }
+
+    #[test]
+    fn plans_adds_updates_and_skips() {
+        let ours = [pin("newer here"), pin("older here")];
+        let mut newer_there = ours[1].clone();
+        newer_there.modified_at = ours[1].modified_at + Duration::minutes(5);
+        let mut older_there = ours[0].clone();
+        older_there.modified_at = ours[0].modified_at - Duration::minutes(5);
+        let same = ours[0].clone();
+
+        let plan = ImportPlan::new(PathBuf::from("b.json"), ours.iter(), vec![pin("new"), newer_there, older_there, same]);
+        let actions: Vec<ImportAction> = plan.entries.iter().map(|(_, a)| *a).collect();
+        assert!(actions == [ImportAction::Add, ImportAction::Update, ImportAction::Skip, ImportAction::Skip]);
+        assert_eq!(plan.count(ImportAction::Add), 1);
+        assert_eq!(plan.count(ImportAction::Update), 1);
+        assert_eq!(plan.count(ImportAction::Skip), 2);
+    }
+
+    #[test]
+    fn round_trips_pins() {
+        let mut pins = vec![pin("a"), pin("b")];
+        pins[1].image = Some("cat.png".to_string());
+        let back = parse(&export(&pins).unwrap()).unwrap();
+        assert!(back == pins);
+    }
+
+    #[test]
+    fn drops_image_names_outside_the_images_directory() {
+        let names = ["/etc/passwd", "../../secret.png", "..", "sub\\x.png", "", "ok.png"];
+        let mut pins: Vec<Pin> = names.iter().map(|_| pin("p")).collect();
+        for (pin, name) in pins.iter_mut().zip(names) {
+            pin.image = Some(name.to_string());
+        }
+        let back = parse(&export(&pins).unwrap()).unwrap();
+        let images: Vec<Option<&str>> = back.iter().map(|p| p.image.as_deref()).collect();
+        assert_eq!(images, [None, None, None, None, None, Some("ok.png")]);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(parse("{}").is_err());
+        let future = export(&[]).unwrap().replace("\"version\": 1", "\"version\": 2");
+        assert!(parse(&future).is_err_and(|e| e.contains("newer")));
+        let other = export(&[]).unwrap().replace(FORMAT, "something-else");
+        assert!(parse(&other).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! `Pin-Board <command>`: pins from shell scripts, git hooks and Makefiles.
+//! Commands go to the running instance when there is one, and straight to
+//! the store otherwise.
+
+use chrono::{Duration, Local};
+use serde_json::{json, Value};
+use std::io::{IsTerminal, Read};
+
+use crate::crypto;
+use crate::ipc::{Reply, Request};
+use crate::migrate::{self, SCHEMA_VERSION};
+use crate::palette;
+use crate::pin::{self, Pin};
+use crate::store;
+use crate::timer::TimerState;
+
+pub const USAGE: &str = "\
+Usage: Pin-Board [command]
+
+With no command, starts Pin-Board or brings up the running dashboard.
+
+Commands:
+  add <text> [--in <time>] [--color <color>]
+                      Pin <text>; its first line is the title. Prints the new id.
+                      --in takes e.g. 15m, 2h, 1h30m or 45s. Put text that starts
+                      with -- after a lone --.
+  pipe [--title <title>] [--tail <n>] [--mono] [--color <color>]
+                      Pin what is piped in, e.g. `make 2>&1 | Pin-Board pipe --tail 20`.
+                      Without --title, the first line is the title.
+  list [--json]       List the pins on the board.
+  done <id>           Mark a pin completed. Any unique start of the id works.
+  hide-all            Hide every pin.
+  show-all            Show every pin.
+  show                Bring up the dashboard, starting Pin-Board if it is not running.
+  help                Show this message.";
+
+pub struct Command {
+    pub request: Request,
+    pub json: bool,
+}
+
+/// `None` means no command: start the app.
+pub fn parse(args: &[String]) -> Result<Option<Command>, String> {
+    let Some((name, rest)) = args.split_first() else { return Ok(None) };
+    let mut json = false;
+
+    let request = match name.as_str() {
+        "add" => {
+            let mut text = Vec::new();
+            let (mut deadline, mut color) = (None, None);
+            let mut rest = rest.iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--" => text.extend(rest.by_ref().map(String::as_str)),
+                    "--in" => {
+                        let value = rest.next().ok_or("--in needs a time, e.g. 15m")?;
+                        deadline = Some(parse_duration(value).and_then(|d| Local::now().checked_add_signed(d)).ok_or(format!("bad time \"{value}\""))?);
+                    }
+                    "--color" => {
+                        color = Some(parse_color(rest.next().ok_or("--color needs a color")?)?);
+                    }
+                    flag if flag.starts_with("--") => return Err(format!("add does not take \"{flag}\"")),
+                    _ => text.push(arg.as_str()),
+                }
+            }
+            let text = text.join(" ");
+            if text.trim().is_empty() {
+                return Err("add needs some text".to_string());
+            }
+            Request::Add { text, title: None, deadline, color, monospace: false }
+        }
+        "pipe" => parse_pipe(rest)?,
+        "list" => {
+            match rest {
+                [] => {}
+                [flag] if flag == "--json" => json = true,
+                _ => return Err("list only takes --json".to_string()),
+            }
+            Request::List
+        }
+        "done" => match rest {
+            [id] => Request::Done { id: id.clone() },
+            _ => return Err("done needs one pin id".to_string()),
+        },
+        "hide-all" => Request::HideAll,
+        "show-all" => Request::ShowAll,
+        "show" => Request::Show,
+        other => return Err(format!("unknown command \"{other}\"")),
+    };
+    Ok(Some(Command { request, json }))
+}
+
+// `some-command | Pin-Board pipe [--title T] [--tail N] [--mono] [--color C]`
+fn parse_pipe(args: &[String]) -> Result<Request, String> {
+    let (mut title, mut tail, mut color, mut monospace) = (None, None, None, false);
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--title" => title = Some(args.next().ok_or("--title needs a title")?.clone()),
+            "--tail" => {
+                let value = args.next().ok_or("--tail needs a number of lines")?;
+                tail = Some(value.parse::<usize>().map_err(|_| format!("bad line count \"{value}\""))?);
+            }
+            "--mono" => monospace = true,
+            "--color" => color = Some(parse_color(args.next().ok_or("--color needs a color")?)?),
+            other => return Err(format!("pipe does not take \"{other}\"")),
+        }
+    }
+
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return Err("pipe reads from stdin, e.g. `make 2>&1 | Pin-Board pipe`".to_string());
+    }
+    let mut raw = Vec::new();
+    stdin.lock().read_to_end(&mut raw).map_err(|e| e.to_string())?;
+    let output = strip_ansi(&String::from_utf8_lossy(&raw));
+
+    // Keep indentation; only blank lines at either end go.
+    let lines: Vec<&str> = output.lines().collect();
+    let start = lines.iter().position(|l| !l.trim().is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|l| !l.trim().is_empty()).map_or(start, |i| i + 1);
+    let mut lines = &lines[start..end];
+    if let Some(n) = tail {
+        lines = &lines[lines.len().saturating_sub(n)..];
+    }
+    let text = lines.join("\n");
+    if text.is_empty() && title.is_none() {
+        return Err("nothing on stdin".to_string());
+    }
+    Ok(Request::Add { text, title, deadline: None, color, monospace })
+}
+
+// Terminal colors and cursor movement would show up as garbage in a pin.
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            if c != '\r' { out.push(c); }
+            continue;
+        }
+        if chars.next_if_eq(&'[').is_some() {
+            // CSI: parameters up to a final byte in @..~
+            while chars.next().is_some_and(|c| !('@'..='~').contains(&c)) {}
+        } else {
+            chars.next();
+        }
+    }
+    out
+}
+
+fn parse_color(value: &str) -> Result<usize, String> {
+    let palette = palette::current();
+    palette.parse(value).ok_or(format!("unknown color \"{value}\" (try {})", palette.names().join(", ")))
+}
+
+/// `15m`, `2h`, `1h30m`, `45s`, `1d`; a bare number is minutes. Signs and
+/// amounts too large to count are refused.
+fn parse_duration(text: &str) -> Option<Duration> {
+    if text.is_empty() {
+        return None;
+    }
+    if text.bytes().all(|b| b.is_ascii_digit()) {
+        return Duration::try_minutes(text.parse().ok()?);
+    }
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in text.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let n: i64 = std::mem::take(&mut number).parse().ok()?;
+        let part = match c {
+            's' => Duration::try_seconds(n),
+            'm' => Duration::try_minutes(n),
+            'h' => Duration::try_hours(n),
+            'd' => Duration::try_days(n),
+            _ => None,
+        };
+        total = total.checked_add(&part?)?;
+    }
+    number.is_empty().then_some(total)
+}
+
+/// Carries out everything but `Show` on `pins`, for the running app and
+/// for `run_offline` alike.
+pub fn apply(request: Request, pins: &mut Vec<Pin>) -> Reply {
+    match request {
+        Request::Show => Ok(String::new()),
+        Request::Add { text, title, deadline, color, monospace } => {
+            let (title, body) = match title {
+                Some(title) => (title, text),
+                None => pin::split_text(&text),
+            };
+            let mut pin = Pin::new(title, body, deadline);
+            if let Some(color) = color {
+                pin.color_idx = color;
+            }
+            pin.monospace = monospace;
+            if !monospace && pin::looks_like_checklist(&pin.content) {
+                pin.make_checklist();
+            }
+            let id = pin.id.clone();
+            pins.push(pin);
+            Ok(id)
+        }
+        Request::List => serde_json::to_string(pins).map_err(|e| e.to_string()),
+        Request::Done { id } => {
+            let idx = find(pins, &id)?;
+            if !pins[idx].is_completed {
+                pins[idx].set_completed(true);
+                pins[idx].modified_at = chrono::Utc::now();
+            }
+            Ok(String::new())
+        }
+        Request::HideAll | Request::ShowAll => {
+            let visible = matches!(request, Request::ShowAll);
+            for pin in pins.iter_mut() {
+                pin.visible = visible;
+            }
+            Ok(String::new())
+        }
+    }
+}
+
+fn find(pins: &[Pin], id: &str) -> Result<usize, String> {
+    let matches: Vec<usize> = (0..pins.len()).filter(|&i| pins[i].id.starts_with(id)).collect();
+    match matches[..] {
+        [idx] => Ok(idx),
+        [] => Err(format!("no pin with id {id}")),
+        _ => Err(format!("{id} matches {} pins; use more of the id", matches.len())),
+    }
+}
+
+/// Runs a command against the store when no instance is running. The
+/// caller holds the instance lock, so the app cannot start meanwhile.
+pub fn run_offline(request: Request) -> Reply {
+    let mut store = store::open_default().ok_or("no data directory")?;
+    let text = store.load().map_err(|e| e.to_string())?.or_else(store::previous_board);
+    if text.as_deref().is_some_and(crypto::is_sealed) {
+        return Err("the board is encrypted; start Pin-Board and unlock it first".to_string());
+    }
+
+    let mut doc: Value = match &text {
+        Some(text) => serde_json::from_str(text).map_err(|e| format!("{} is unreadable ({e}); start Pin-Board to recover it", store.path().display()))?,
+        None => json!({ "schema_version": SCHEMA_VERSION, "pins": [], "global_timer": TimerState::default() }),
+    };
+    let version = doc["schema_version"].as_u64();
+    migrate::migrate(&mut doc).map_err(|e| e.to_string())?;
+    let mut pins: Vec<Pin> = serde_json::from_value(doc["pins"].clone()).map_err(|e| e.to_string())?;
+
+    // Single-pin writes need a board in the current layout to write into.
+    if text.is_none() || version != Some(SCHEMA_VERSION) {
+        store.save(&doc).map_err(|e| e.to_string())?;
+    }
+
+    let before = pins.clone();
+    let reply = apply(request, &mut pins);
+    let path = store.path().display().to_string();
+    let failed = |e: std::io::Error| format!("failed to save {path}: {e}");
+    for pin in pins.iter().filter(|p| !before.contains(p)) {
+        store.upsert(pin).map_err(failed)?;
+    }
+    for pin in before.iter().filter(|b| !pins.iter().any(|p| p.id == b.id)) {
+        store.delete(&pin.id).map_err(failed)?;
+    }
+    reply
+}
+
+/// Prints a reply the way the command's caller expects it.
+pub fn print(command: &Command, reply: Reply) -> Result<(), String> {
+    let out = reply?;
+    if !matches!(command.request, Request::List) || command.json {
+        if !out.is_empty() {
+            println!("{out}");
+        }
+        return Ok(());
+    }
+
+    let pins: Vec<Pin> = serde_json::from_str(&out).map_err(|e| e.to_string())?;
+    for pin in &pins {
+        let title = if pin.title.is_empty() { pin.content.lines().next().unwrap_or("") } else { &pin.title };
+        let mut line = format!("{}  {}  {title}", pin.id.chars().take(8).collect::<String>(), if pin.is_completed { "[x]" } else { "[ ]" });
+        if let Some(deadline) = pin.deadline {
+            line.push_str(&format!("  (due {})", deadline.format("%b %-d %H:%M")));
+        }
+        for tag in &pin.tags {
+            line.push_str(&format!("  #{tag}"));
+        }
+        if let Some((done, total)) = pin.progress() {
+            line.push_str(&format!("  ({done}/{total})"));
+        }
+        if pin.image.is_some() {
+            line.push_str("  (image)");
+        }
+        if !pin.visible {
+            line.push_str("  (hidden)");
+        }
+        println!("{line}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn parses_add() {
+        let command = parse(&args("add fix the build --in 15m --color 1")).unwrap().unwrap();
+        let Request::Add { text, deadline, color, .. } = command.request else { panic!("not an add") };
+        assert_eq!(text, "fix the build");
+        assert!(deadline.is_some());
+        assert_eq!(color, Some(1));
+
+        let command = parse(&args("add -- --verbose broke it")).unwrap().unwrap();
+        assert!(matches!(command.request, Request::Add { text, .. } if text == "--verbose broke it"));
+    }
+
+    #[test]
+    fn rejects_unknown_flags() {
+        assert!(parse(&args("add fix build --colour red")).is_err());
+        assert!(parse(&args("add fix build --color")).is_err());
+        assert!(parse(&args("add --in 15m")).is_err());
+        assert!(parse(&args("list --all")).is_err());
+        assert!(parse(&args("frobnicate")).is_err());
+    }
+
+    #[test]
+    fn documents_every_command() {
+        for name in ["add", "pipe", "list", "done", "hide-all", "show-all", "show", "help"] {
+            assert!(USAGE.lines().any(|l| l.trim_start().starts_with(name)), "{name}");
+        }
+        assert!(parse(&args("show")).unwrap().is_some_and(|c| matches!(c.request, Request::Show)));
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("15"), Some(Duration::minutes(15)));
+        assert_eq!(parse_duration("45s"), Some(Duration::seconds(45)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("1d2h"), Some(Duration::hours(26)));
+    }
+
+    #[test]
+    fn rejects_bad_durations() {
+        for text in ["", "-5", "+5", "-5m", "m", "5x", "1h30", "1.5h", "99999999999999999999", "9999999999999999d"] {
+            assert_eq!(parse_duration(text), None, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn strips_terminal_escapes() {
+        assert_eq!(strip_ansi("\x1b[1;31merror\x1b[0m: done\r"), "error: done");
+    }
+}
@@ -0,0 +1,192 @@
+//! Passphrase encryption for the stored board (see docs/STORAGE.md). The key
+//! is derived with Argon2id and the board sealed with XChaCha20-Poly1305; the
+//! store then holds an envelope like this instead of the board:
+//!
+//! ```text
+//! { "format": "pin-board-encrypted", "version": 1,
+//!   "kdf": "argon2id", "kdf_params": { "m_cost": 19456, "t_cost": 2, "p_cost": 1 },
+//!   "salt": "...", "cipher": "xchacha20poly1305", "nonce": "...", "ciphertext": "..." }
+//! ```
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use zeroize::Zeroizing;
+
+const FORMAT: &str = "pin-board-encrypted";
+const VERSION: u64 = 1;
+const KDF: &str = "argon2id";
+const CIPHER: &str = "xchacha20poly1305";
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct KdfParams {
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self { m_cost: Params::DEFAULT_M_COST, t_cost: Params::DEFAULT_T_COST, p_cost: Params::DEFAULT_P_COST }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    format: String,
+    version: u64,
+    kdf: String,
+    kdf_params: KdfParams,
+    salt: String,
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug)]
+pub enum CryptoError {
+    WrongPassphrase,
+    Malformed(String),
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CryptoError::WrongPassphrase => write!(f, "wrong passphrase"),
+            CryptoError::Malformed(why) => write!(f, "encrypted board is damaged ({why})"),
+        }
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+/// A derived key, kept while the board is unlocked so saves need no passphrase.
+pub struct Key {
+    key: Zeroizing<[u8; 32]>,
+    salt: [u8; 16],
+    params: KdfParams,
+}
+
+impl Key {
+    /// A key for a new passphrase, with a fresh salt.
+    pub fn new(passphrase: &str) -> Result<Self, CryptoError> {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(passphrase, salt, KdfParams::default())
+    }
+
+    fn derive(passphrase: &str, salt: [u8; 16], params: KdfParams) -> Result<Self, CryptoError> {
+        let argon_params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(32))
+            .map_err(|e| CryptoError::Malformed(e.to_string()))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params)
+            .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut())
+            .map_err(|e| CryptoError::Malformed(e.to_string()))?;
+        Ok(Self { key, salt, params })
+    }
+
+    /// Encrypts `doc` under a fresh nonce and wraps it in an envelope.
+    pub fn seal(&self, doc: &Value) -> Result<Value, CryptoError> {
+        let plaintext = Zeroizing::new(serde_json::to_vec(doc).map_err(|e| CryptoError::Malformed(e.to_string()))?);
+        let cipher = XChaCha20Poly1305::new(self.key.as_ref().into());
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|e| CryptoError::Malformed(e.to_string()))?;
+
+        let envelope = Envelope {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            kdf: KDF.to_string(),
+            kdf_params: self.params,
+            salt: BASE64.encode(self.salt),
+            cipher: CIPHER.to_string(),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        serde_json::to_value(envelope).map_err(|e| CryptoError::Malformed(e.to_string()))
+    }
+}
+
+/// Whether `text` is an encrypted board rather than a plain one.
+pub fn is_sealed(text: &str) -> bool {
+    serde_json::from_str::<Value>(text).is_ok_and(|v| v["format"] == FORMAT)
+}
+
+/// Decrypts a sealed board, returning its text and the key to seal it again.
+pub fn open(text: &str, passphrase: &str) -> Result<(Zeroizing<String>, Key), CryptoError> {
+    let malformed = |e: &dyn fmt::Display| CryptoError::Malformed(e.to_string());
+    let envelope: Envelope = serde_json::from_str(text).map_err(|e| malformed(&e))?;
+    if envelope.version > VERSION || envelope.kdf != KDF || envelope.cipher != CIPHER {
+        return Err(CryptoError::Malformed(format!("unsupported {} v{}", envelope.cipher, envelope.version)));
+    }
+
+    let salt: [u8; 16] = BASE64
+        .decode(&envelope.salt)
+        .map_err(|e| malformed(&e))?
+        .try_into()
+        .map_err(|_| CryptoError::Malformed("bad salt".to_string()))?;
+    let nonce = BASE64.decode(&envelope.nonce).map_err(|e| malformed(&e))?;
+    if nonce.len() != 24 {
+        return Err(CryptoError::Malformed("bad nonce".to_string()));
+    }
+    let ciphertext = BASE64.decode(&envelope.ciphertext).map_err(|e| malformed(&e))?;
+
+    let key = Key::derive(passphrase, salt, envelope.kdf_params)?;
+    let cipher = XChaCha20Poly1305::new(key.key.as_ref().into());
+    // The tag covers the ciphertext, so a wrong key and a tampered file look alike.
+    let plaintext = cipher
+        .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| CryptoError::WrongPassphrase)?;
+    let text = String::from_utf8(plaintext).map_err(|e| malformed(&e))?;
+    Ok((Zeroizing::new(text), key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // The real cost makes unoptimized test builds crawl.
+    fn cheap_key(passphrase: &str) -> Key {
+        Key::derive(passphrase, [7; 16], KdfParams { m_cost: 64, t_cost: 1, p_cost: 1 }).unwrap()
+    }
+
+    #[test]
+    fn seals_and_opens() {
+        let doc = json!({ "schema_version": 2, "pins": [{ "title": "secret" }] });
+        let sealed = cheap_key("correct horse").seal(&doc).unwrap().to_string();
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("secret"));
+        assert!(!is_sealed(&doc.to_string()));
+
+        let (text, key) = open(&sealed, "correct horse").unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&text).unwrap(), doc);
+        // The key that came back seals again under a new nonce.
+        let again = key.seal(&doc).unwrap().to_string();
+        assert_ne!(again, sealed);
+        assert_eq!(serde_json::from_str::<Value>(&open(&again, "correct horse").unwrap().0).unwrap(), doc);
+    }
+
+    #[test]
+    fn refuses_a_wrong_passphrase() {
+        let sealed = cheap_key("correct horse").seal(&json!({ "pins": [] })).unwrap().to_string();
+        assert!(matches!(open(&sealed, "battery staple"), Err(CryptoError::WrongPassphrase)));
+        assert!(matches!(open(&sealed, ""), Err(CryptoError::WrongPassphrase)));
+    }
+
+    #[test]
+    fn reports_damaged_envelopes() {
+        let mut sealed = cheap_key("pw").seal(&json!({})).unwrap();
+        sealed["nonce"] = json!("AAAA");
+        assert!(matches!(open(&sealed.to_string(), "pw"), Err(CryptoError::Malformed(_))));
+        sealed["version"] = json!(VERSION + 1);
+        assert!(matches!(open(&sealed.to_string(), "pw"), Err(CryptoError::Malformed(_))));
+        assert!(matches!(open("{ not json", "pw"), Err(CryptoError::Malformed(_))));
+    }
+}
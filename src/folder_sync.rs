@@ -0,0 +1,219 @@
+//! Mirrors each pin to `<dir>/<id>.md`, so notes can be grepped, versioned
+//! and edited outside the app:
+//!
+//! ```text
+//! ---
+//! title: Deploy at 5
+//! done: false
+//! color: blue
+//! opacity: 0.95
+//! deadline: 2026-10-18T17:00:00+02:00
+//! ---
+//!
+//! Check the staging logs first
+//! ```
+
+use eframe::egui;
+use notify::{RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::markdown;
+use crate::pin::Pin;
+use crate::store;
+
+// Typing in a pin rewrites its file at most this often.
+const WRITE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A change made to the folder from outside the app.
+pub enum Change {
+    Edited { id: String, text: String },
+    Removed(String),
+}
+
+pub struct FolderSync {
+    dir: PathBuf,
+    _watcher: Option<notify::RecommendedWatcher>,
+    events: mpsc::Receiver<PathBuf>,
+    // Each file as we last read or wrote it; a file that still matches was
+    // our own write.
+    seen: HashMap<String, String>,
+    // Each pin as we last wrote or adopted it, so outside edits are not
+    // rewritten until the pin changes on the board.
+    rendered: HashMap<String, String>,
+    pending: Vec<Change>,
+    last_write: Option<Instant>,
+}
+
+impl FolderSync {
+    /// Starts watching `dir`. Files already in it come back from the first
+    /// `changes()`, so an existing folder is merged rather than overwritten.
+    pub fn start(dir: PathBuf, ctx: &egui::Context) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+
+        let (tx, events) = mpsc::channel();
+        let ctx = ctx.clone();
+        let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+                ctx.request_repaint();
+            }
+        })
+        .and_then(|mut w| w.watch(&dir, RecursiveMode::NonRecursive).map(|_| w));
+        let watcher = match watcher {
+            Ok(w) => Some(w),
+            Err(e) => {
+                eprintln!("Failed to watch {}, outside edits will not show up: {e}", dir.display());
+                None
+            }
+        };
+
+        let mut sync = Self { dir, _watcher: watcher, events, seen: HashMap::new(), rendered: HashMap::new(), pending: Vec::new(), last_write: None };
+        for entry in fs::read_dir(&sync.dir)?.flatten() {
+            sync.check(&entry.path());
+        }
+        Ok(sync)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Outside edits since the last call.
+    pub fn changes(&mut self) -> Vec<Change> {
+        let paths: Vec<PathBuf> = self.events.try_iter().collect();
+        for path in paths {
+            self.check(&path);
+        }
+        std::mem::take(&mut self.pending)
+    }
+
+    fn check(&mut self, path: &Path) {
+        if path.extension().is_none_or(|ext| ext != "md") {
+            return;
+        }
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else { return };
+
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                if self.seen.get(&id) == Some(&text) {
+                    return;
+                }
+                self.seen.insert(id.clone(), text.clone());
+                self.pending.push(Change::Edited { id, text });
+            }
+            // Only files we knew about count as deleted; editors that save by
+            // renaming put the file back before we look.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                if self.seen.remove(&id).is_some() {
+                    self.rendered.remove(&id);
+                    self.pending.push(Change::Removed(id));
+                }
+            }
+            Err(e) => eprintln!("Failed to read {}: {e}", path.display()),
+        }
+    }
+
+    /// Writes pins whose files are out of date and removes files of pins that
+    /// left the board.
+    pub fn write(&mut self, pins: &[Pin]) {
+        if self.last_write.is_some_and(|t| t.elapsed() < WRITE_INTERVAL) {
+            return;
+        }
+        self.last_write = Some(Instant::now());
+
+        for pin in pins {
+            let text = render(pin);
+            if self.rendered.get(&pin.id) == Some(&text) {
+                continue;
+            }
+            match store::write_atomic(&self.file(&pin.id), text.as_bytes()) {
+                Ok(()) => {
+                    self.seen.insert(pin.id.clone(), text.clone());
+                    self.rendered.insert(pin.id.clone(), text);
+                }
+                Err(e) => eprintln!("Failed to write {}: {e}", self.file(&pin.id).display()),
+            }
+        }
+
+        let gone: Vec<String> = self.rendered.keys().filter(|id| !pins.iter().any(|p| &p.id == *id)).cloned().collect();
+        for id in gone {
+            self.rendered.remove(&id);
+            self.seen.remove(&id);
+            if let Err(e) = fs::remove_file(self.file(&id)) {
+                if e.kind() != io::ErrorKind::NotFound {
+                    eprintln!("Failed to remove {}: {e}", self.file(&id).display());
+                }
+            }
+        }
+    }
+
+    /// Records `pin` as up to date with its file after an outside edit.
+    pub fn adopt(&mut self, pin: &Pin) {
+        self.rendered.insert(pin.id.clone(), render(pin));
+    }
+
+    fn file(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.md"))
+    }
+}
+
+fn render(pin: &Pin) -> String {
+    let mut fields = vec![("title", pin.title.clone()), ("done", pin.is_completed.to_string())];
+    // The file name is the id.
+    fields.extend(markdown::fields(pin).into_iter().filter(|(k, _)| *k != "id"));
+    let mut out = markdown::write_front_matter(&fields);
+    let body = pin.body_text();
+    if !body.is_empty() {
+        out.push('\n');
+        out.push_str(&body);
+        out.push('\n');
+    }
+    out
+}
+
+/// Applies a pin file onto `pin`. A file without front matter is plain text,
+/// with an optional `# Heading` as the title.
+pub fn apply(text: &str, pin: &mut Pin) {
+    let before = pin.clone();
+    let lines: Vec<&str> = text.lines().collect();
+    let (meta, mut body) = markdown::read_front_matter(&lines);
+
+    let mut done = None;
+    let mut title = None;
+    for (key, value) in &meta {
+        match key.as_str() {
+            "title" => title = Some(value.clone()),
+            "done" => done = Some(value == "true" || value == "yes"),
+            // Ours to keep; the file's copy goes stale as soon as someone edits it.
+            "id" | "modified" => {}
+            _ => markdown::apply_field(pin, key, value),
+        }
+    }
+
+    let start = body.iter().position(|l| !l.trim().is_empty()).unwrap_or(body.len());
+    body = &body[start..];
+    if title.is_none() {
+        if let Some(heading) = body.first().and_then(|l| l.strip_prefix("# ")) {
+            title = Some(heading.trim().to_string());
+            body = &body[1..];
+        }
+    }
+    if let Some(title) = title {
+        pin.title = title;
+    }
+    if let Some(done) = done.filter(|d| *d != pin.is_completed) {
+        pin.set_completed(done);
+    }
+    pin.set_body_text(body.join("\n").trim().to_string());
+
+    if *pin != before {
+        pin.modified_at = chrono::Utc::now();
+    }
+}
@@ -0,0 +1,292 @@
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+use crate::pin::Pin;
+use crate::trash::TrashedPin;
+
+const LIMIT: usize = 100;
+// Typing into one pin with pauses shorter than this undoes as one step.
+const COALESCE: Duration = Duration::from_millis(750);
+
+#[derive(Clone, Copy)]
+pub enum HistoryAction {
+    Undo,
+    Redo,
+}
+
+impl HistoryAction {
+    /// Consumes Ctrl+Z / Ctrl+Shift+Z (and Ctrl+Y) from the viewport `ctx` belongs to,
+    /// before any `TextEdit` gets to apply its own undo.
+    pub fn from_shortcuts(ctx: &egui::Context) -> Option<Self> {
+        let redo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z);
+        let redo_alt = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y);
+        let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+
+        ctx.input_mut(|i| {
+            // Redo first: Ctrl+Z also matches Ctrl+Shift+Z.
+            if i.consume_shortcut(&redo) || i.consume_shortcut(&redo_alt) {
+                Some(HistoryAction::Redo)
+            } else if i.consume_shortcut(&undo) {
+                Some(HistoryAction::Undo)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// The undoable part of the board. Window geometry and open menus are left
+/// out so that resizing a pin does not bury real edits.
+#[derive(Clone, PartialEq)]
+pub struct Snapshot {
+    pub pins: Vec<Pin>,
+    pub archive: Vec<Pin>,
+    pub trash: Vec<TrashedPin>,
+}
+
+impl Snapshot {
+    pub fn capture(pins: &[Pin], archive: &[Pin], trash: &[TrashedPin]) -> Self {
+        let strip = |p: &Pin| Pin { size: None, position: None, placed: false, show_menu: false, new_item: String::new(), new_tag: String::new(), ..p.clone() };
+        Self {
+            pins: pins.iter().map(strip).collect(),
+            archive: archive.iter().map(strip).collect(),
+            trash: trash.iter().map(|t| TrashedPin { pin: strip(&t.pin), ..t.clone() }).collect(),
+        }
+    }
+}
+
+/// What kind of change a recorded snapshot follows.
+#[derive(Clone, PartialEq)]
+pub enum Edit {
+    /// Typing into the pin with this id.
+    Text(String),
+    /// Anything else: a color, a lock, a delete, CLEAN, an import.
+    Step,
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    current: Option<Snapshot>,
+    // The pin last typed into, and when, while typing can still merge.
+    typing: Option<(String, Instant)>,
+}
+
+impl History {
+    /// Starts over from a freshly loaded board.
+    pub fn reset(&mut self, now: Snapshot) {
+        *self = Self { current: Some(now), ..Self::default() };
+    }
+
+    /// Records the board right after `edit` was made to it.
+    pub fn record(&mut self, now: Snapshot, edit: Edit) {
+        self.record_at(now, edit, Instant::now());
+    }
+
+    fn record_at(&mut self, now: Snapshot, edit: Edit, at: Instant) {
+        let Some(prev) = self.current.take() else {
+            self.current = Some(now);
+            return;
+        };
+        if prev == now {
+            self.current = Some(prev);
+            return;
+        }
+
+        // Only a run of typing into one pin undoes as one step.
+        let merging = match (&edit, &self.typing) {
+            (Edit::Text(id), Some((last, t))) => id == last && at.duration_since(*t) < COALESCE,
+            _ => false,
+        };
+        if !merging {
+            self.undo.push(prev);
+            if self.undo.len() > LIMIT {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.current = Some(now);
+        self.typing = match edit {
+            Edit::Text(id) => Some((id, at)),
+            Edit::Step => None,
+        };
+    }
+
+    /// Applies a palette reorder to every recorded board.
+    pub fn remap_colors(&mut self, map: impl Fn(usize) -> usize) {
+        let snapshots = self.undo.iter_mut().chain(self.redo.iter_mut()).chain(self.current.as_mut());
+        for snapshot in snapshots {
+            let pins = snapshot.pins.iter_mut().chain(snapshot.archive.iter_mut()).chain(snapshot.trash.iter_mut().map(|t| &mut t.pin));
+            for pin in pins {
+                pin.color_idx = map(pin.color_idx);
+            }
+        }
+    }
+
+    pub fn undo(&mut self) -> Option<Snapshot> {
+        let target = self.undo.pop()?;
+        self.step(target, |h| &mut h.redo)
+    }
+
+    pub fn redo(&mut self) -> Option<Snapshot> {
+        let target = self.redo.pop()?;
+        self.step(target, |h| &mut h.undo)
+    }
+
+    fn step(&mut self, target: Snapshot, other: fn(&mut Self) -> &mut Vec<Snapshot>) -> Option<Snapshot> {
+        if let Some(current) = self.current.replace(target.clone()) {
+            other(self).push(current);
+        }
+        self.typing = None;
+        Some(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(titles: &[&str]) -> Vec<Pin> {
+        titles.iter().map(|t| Pin::new(t.to_string(), String::new(), None)).collect()
+    }
+
+    fn snapshot(pins: &[Pin]) -> Snapshot {
+        Snapshot::capture(pins, &[], &[])
+    }
+
+    fn titles(snapshot: &Snapshot) -> Vec<&str> {
+        snapshot.pins.iter().map(|p| p.title.as_str()).collect()
+    }
+
+    #[test]
+    fn undoes_and_redoes_steps() {
+        let mut pins = board(&["a"]);
+        let mut history = History::default();
+        history.reset(snapshot(&pins));
+        assert!(history.undo().is_none());
+
+        pins[0].color_idx = 2;
+        history.record(snapshot(&pins), Edit::Step);
+        pins[0].is_locked = true;
+        history.record(snapshot(&pins), Edit::Step);
+
+        let undone = history.undo().unwrap();
+        assert!(!undone.pins[0].is_locked);
+        assert_eq!(undone.pins[0].color_idx, 2);
+        assert_eq!(history.undo().unwrap().pins[0].color_idx, 0);
+        assert!(history.undo().is_none());
+        assert_eq!(history.redo().unwrap().pins[0].color_idx, 2);
+        assert!(history.redo().unwrap().pins[0].is_locked);
+        assert!(history.redo().is_none());
+    }
+
+    #[test]
+    fn a_new_edit_drops_the_redo_steps() {
+        let mut pins = board(&["a"]);
+        let mut history = History::default();
+        history.reset(snapshot(&pins));
+        pins[0].color_idx = 1;
+        history.record(snapshot(&pins), Edit::Step);
+        history.undo();
+
+        pins[0].color_idx = 3;
+        history.record(snapshot(&pins), Edit::Step);
+        assert!(history.redo().is_none());
+        assert_eq!(history.undo().unwrap().pins[0].color_idx, 0);
+    }
+
+    #[test]
+    fn merges_typing_into_one_pin() {
+        let mut pins = board(&["", "other"]);
+        let id = pins[0].id.clone();
+        let mut history = History::default();
+        history.reset(snapshot(&pins));
+
+        let start = Instant::now();
+        for (n, title) in ["h", "he", "hel", "hello"].iter().enumerate() {
+            pins[0].title = title.to_string();
+            history.record_at(snapshot(&pins), Edit::Text(id.clone()), start + COALESCE / 2 * n as u32);
+        }
+        // A pause starts a new step.
+        pins[0].title = "hello!".to_string();
+        history.record_at(snapshot(&pins), Edit::Text(id.clone()), start + COALESCE * 4);
+        // So does typing into another pin.
+        pins[1].title = "other pin".to_string();
+        history.record_at(snapshot(&pins), Edit::Text(pins[1].id.clone()), start + COALESCE * 4);
+
+        assert_eq!(titles(&history.undo().unwrap()), ["hello!", "other"]);
+        assert_eq!(titles(&history.undo().unwrap()), ["hello", "other"]);
+        assert_eq!(titles(&history.undo().unwrap()), ["", "other"]);
+        assert!(history.undo().is_none());
+    }
+
+    #[test]
+    fn a_delete_right_after_typing_is_its_own_step() {
+        let mut pins = board(&["a", "b"]);
+        let id = pins[0].id.clone();
+        let mut history = History::default();
+        history.reset(snapshot(&pins));
+
+        let start = Instant::now();
+        pins[0].title = "typed".to_string();
+        history.record_at(snapshot(&pins), Edit::Text(id.clone()), start);
+        pins.remove(1);
+        history.record_at(snapshot(&pins), Edit::Step, start);
+        // Typing straight after the delete does not fold back into the first run.
+        pins[0].title = "typed more".to_string();
+        history.record_at(snapshot(&pins), Edit::Text(id), start);
+
+        assert_eq!(titles(&history.undo().unwrap()), ["typed"]);
+        assert_eq!(titles(&history.undo().unwrap()), ["typed", "b"]);
+        assert_eq!(titles(&history.undo().unwrap()), ["a", "b"]);
+    }
+
+    #[test]
+    fn ignores_window_geometry_and_unchanged_boards() {
+        let mut pins = board(&["a"]);
+        let mut history = History::default();
+        history.reset(snapshot(&pins));
+        pins[0].size = Some((300.0, 200.0));
+        pins[0].position = Some((10.0, 10.0));
+        history.record(snapshot(&pins), Edit::Step);
+        history.record(snapshot(&pins), Edit::Step);
+        assert!(history.undo().is_none());
+    }
+
+    #[test]
+    fn follows_a_palette_reorder() {
+        let mut pins = board(&["a", "b"]);
+        let mut history = History::default();
+        history.reset(snapshot(&pins));
+        pins[0].color_idx = 1;
+        history.record(snapshot(&pins), Edit::Step);
+        pins[1].color_idx = 2;
+        history.record(snapshot(&pins), Edit::Step);
+
+        // Colors 0 and 1 swap places.
+        let swap = |i| match i { 0 => 1, 1 => 0, i => i };
+        for pin in &mut pins { pin.color_idx = swap(pin.color_idx); }
+        history.remap_colors(swap);
+
+        let undone = history.undo().unwrap();
+        assert_eq!(undone.pins.iter().map(|p| p.color_idx).collect::<Vec<_>>(), [0, 1]);
+        let undone = history.undo().unwrap();
+        assert_eq!(undone.pins.iter().map(|p| p.color_idx).collect::<Vec<_>>(), [1, 1]);
+        assert_eq!(history.redo().unwrap().pins[0].color_idx, 0);
+    }
+
+    #[test]
+    fn keeps_at_most_the_limit() {
+        let mut pins = board(&["a"]);
+        let mut history = History::default();
+        history.reset(snapshot(&pins));
+        for n in 0..LIMIT + 10 {
+            pins[0].color_idx = n + 1;
+            history.record(snapshot(&pins), Edit::Step);
+        }
+        let mut steps = 0;
+        while history.undo().is_some() { steps += 1; }
+        assert_eq!(steps, LIMIT);
+    }
+}
@@ -0,0 +1,109 @@
+//! Images shown on pins. Each is a file in `<data dir>/images`, named by
+//! `Pin::image`, next to the board itself.
+
+use eframe::egui;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::store;
+
+// Bigger images are scaled down for display; the file keeps full size.
+const MAX_TEXTURE_SIDE: u32 = 2048;
+
+pub fn dir() -> Option<PathBuf> {
+    store::data_dir().map(|d| d.join("images"))
+}
+
+fn no_dir() -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, "no data directory")
+}
+
+/// Whether `name` can only mean a file in the images directory. Names from
+/// imported files must pass this, or a pin could show any file on disk.
+pub fn is_plain_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+/// Whether `path` names a kind of image that can be pinned.
+pub fn is_image(path: &Path) -> bool {
+    image::ImageFormat::from_path(path).is_ok_and(|f| f.reading_enabled())
+}
+
+/// Asks for an image file.
+pub fn choose() -> Option<PathBuf> {
+    rfd::FileDialog::new().add_filter("Images", &["png", "jpg", "jpeg", "gif", "webp", "bmp"]).pick_file()
+}
+
+/// Copies an image file in, returning its new name.
+pub fn import(path: &Path) -> io::Result<String> {
+    let format = image::ImageReader::open(path)?
+        .with_guessed_format()?
+        .format()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("{} is not an image", path.display())))?;
+    let name = format!("{}.{}", Uuid::new_v4(), format.extensions_str()[0]);
+    let dir = dir().ok_or_else(no_dir)?;
+    fs::create_dir_all(&dir)?;
+    fs::copy(path, dir.join(&name))?;
+    Ok(name)
+}
+
+/// Saves the image on the clipboard as a PNG, returning its name.
+pub fn paste() -> Result<String, String> {
+    let data = arboard::Clipboard::new()
+        .and_then(|mut c| c.get_image())
+        .map_err(|_| "The clipboard has no image.".to_string())?;
+    let image = image::RgbaImage::from_raw(data.width as u32, data.height as u32, data.bytes.into_owned())
+        .ok_or("The clipboard image is damaged.")?;
+    let name = format!("{}.png", Uuid::new_v4());
+    let dir = dir().ok_or("No data directory.")?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    image.save(dir.join(&name)).map_err(|e| e.to_string())?;
+    Ok(name)
+}
+
+/// The texture for an image, decoded the first time it is drawn. `None`
+/// if the file is missing or unreadable.
+pub fn texture(ctx: &egui::Context, name: &str) -> Option<egui::TextureHandle> {
+    let id = egui::Id::new(("pin_image", name));
+    if let Some(cached) = ctx.data(|d| d.get_temp::<Option<egui::TextureHandle>>(id)) {
+        return cached;
+    }
+    let texture = match load(name) {
+        Ok(image) => Some(ctx.load_texture(name, image, egui::TextureOptions::LINEAR)),
+        Err(e) => {
+            eprintln!("Failed to load image {name}: {e}");
+            None
+        }
+    };
+    ctx.data_mut(|d| d.insert_temp(id, texture.clone()));
+    texture
+}
+
+fn load(name: &str) -> Result<egui::ColorImage, Box<dyn std::error::Error>> {
+    let path = dir().ok_or_else(no_dir)?.join(name);
+    let mut image = image::ImageReader::open(path)?.with_guessed_format()?.decode()?;
+    if image.width().max(image.height()) > MAX_TEXTURE_SIDE {
+        image = image.thumbnail(MAX_TEXTURE_SIDE, MAX_TEXTURE_SIDE);
+    }
+    let rgba = image.to_rgba8();
+    let size = [rgba.width() as usize, rgba.height() as usize];
+    Ok(egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_raw()))
+}
+
+/// Deletes images that no pin refers to any more.
+pub fn remove_unused<'a>(used: impl IntoIterator<Item = &'a str>) {
+    let Some(dir) = dir() else { return };
+    let Ok(entries) = fs::read_dir(&dir) else { return };
+    let used: HashSet<&str> = used.into_iter().collect();
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        if name.to_str().is_some_and(|n| !used.contains(n)) {
+            if let Err(e) = fs::remove_file(entry.path()) {
+                eprintln!("Failed to remove {}: {e}", entry.path().display());
+            }
+        }
+    }
+}
@@ -0,0 +1,384 @@
+//! Importers for the sticky-note apps people usually move from. Each keeps the
+//! source's own note id, so importing the same notes twice updates them.
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::palette;
+use crate::pin::{self, Pin};
+use crate::store;
+
+#[derive(Clone, Copy)]
+pub enum Source {
+    Xpad,
+    Stickynotes,
+    Tomboy,
+}
+
+impl Source {
+    pub const ALL: [Source; 3] = [Source::Xpad, Source::Stickynotes, Source::Tomboy];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Source::Xpad => "Xpad…",
+            Source::Stickynotes => "Indicator Stickynotes…",
+            Source::Tomboy => "Tomboy / Gnote…",
+        }
+    }
+
+    /// Xpad and Tomboy keep one file per note, so they are imported by folder.
+    pub fn reads_folder(self) -> bool {
+        !matches!(self, Source::Stickynotes)
+    }
+
+    /// Where the app keeps its notes by default, if that exists on this machine.
+    pub fn default_location(self) -> Option<PathBuf> {
+        let home = store::home_dir()?;
+        let config = xdg_dir("XDG_CONFIG_HOME").unwrap_or_else(|| home.join(".config"));
+        let data = xdg_dir("XDG_DATA_HOME").unwrap_or_else(|| home.join(".local").join("share"));
+        let candidates = match self {
+            Source::Xpad => vec![config.join("xpad"), home.join(".xpad")],
+            Source::Stickynotes => vec![config.join("indicator-stickynotes"), home.join(".indicator-stickynotes")],
+            Source::Tomboy => vec![data.join("gnote"), data.join("tomboy"), home.join(".tomboy")],
+        };
+        candidates.into_iter().find(|p| p.exists())
+    }
+
+    pub fn import(self, path: &Path) -> Result<Vec<Pin>, String> {
+        match self {
+            Source::Xpad => xpad(path),
+            Source::Stickynotes => {
+                let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+                stickynotes(&text)
+            }
+            Source::Tomboy => tomboy_dir(path),
+        }
+    }
+}
+
+fn xdg_dir(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var).map(PathBuf::from).filter(|p| p.is_absolute())
+}
+
+fn modified_time(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).and_then(|m| m.modified()).ok().map(DateTime::<Utc>::from)
+}
+
+// Xpad: an `info-XXXXXX` file of `key value` lines per note, pointing at a
+// `content-XXXXXX` file with the plain text.
+fn xpad(dir: &Path) -> Result<Vec<Pin>, String> {
+    let mut infos: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("info-")))
+        .collect();
+    if infos.is_empty() {
+        return Err(format!("no Xpad notes in {}", dir.display()));
+    }
+    infos.sort();
+
+    let mut pins = Vec::new();
+    for info_path in infos {
+        let info = fs::read_to_string(&info_path).map_err(|e| e.to_string())?;
+        let suffix = info_path.file_name().and_then(|n| n.to_str()).unwrap_or_default().trim_start_matches("info-");
+        let field = |key: &str| {
+            info.lines().find_map(|l| l.split_once(' ').filter(|(k, _)| *k == key).map(|(_, v)| v.trim()))
+        };
+
+        let content_path = dir.join(field("content").map_or_else(|| format!("content-{suffix}"), str::to_string));
+        let Ok(text) = fs::read_to_string(&content_path) else {
+            eprintln!("Skipping Xpad note {suffix}: no content file");
+            continue;
+        };
+
+        let (title, content) = pin::split_text(&text);
+        let mut pin = Pin::new(title, content, None);
+        pin.id = format!("xpad-{suffix}");
+        if let Some((r, g, b)) = field("back").and_then(parse_rgb) {
+            pin.color_idx = palette::current().nearest(r, g, b);
+        }
+        if let (Some(w), Some(h)) = (field("width").and_then(|v| v.parse().ok()), field("height").and_then(|v| v.parse().ok())) {
+            pin.size = Some((w, h));
+        }
+        pin.visible = field("hidden") != Some("1");
+        pin.is_always_on_top = field("sticky") == Some("1");
+        if let Some(t) = modified_time(&content_path) {
+            pin.created_at = t;
+            pin.modified_at = t;
+        }
+        pins.push(pin);
+    }
+    Ok(pins)
+}
+
+/// Reads the color strings Xpad has written over the years:
+/// `rgb(255,238,153)`, `rgb:ffff/eeee/9999`, `#ffee99` and `65535 61166 39321`.
+fn parse_rgb(value: &str) -> Option<(u8, u8, u8)> {
+    let value = value.trim();
+    let channels: Vec<u8> = if let Some(inner) = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb(")) {
+        inner.trim_end_matches(')').split(',').take(3).map(|c| c.trim().parse().ok()).collect::<Option<_>>()?
+    } else if let Some(hex) = value.strip_prefix("rgb:") {
+        // 1–4 hex digits per channel; keep the top byte.
+        hex.split('/')
+            .map(|c| {
+                if !(1..=4).contains(&c.len()) {
+                    return None;
+                }
+                let v = u16::from_str_radix(c, 16).ok()?;
+                let max = (1u32 << (4 * c.len())) - 1;
+                Some((v as u32 * 255 / max) as u8)
+            })
+            .collect::<Option<_>>()?
+    } else if let Some(hex) = value.strip_prefix('#').filter(|h| h.len() == 6 && h.is_ascii()) {
+        (0..3).map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()).collect::<Option<_>>()?
+    } else {
+        value.split_whitespace().take(3).map(|c| c.parse::<u16>().ok().map(|v| (v >> 8) as u8)).collect::<Option<_>>()?
+    };
+    match channels[..] {
+        [r, g, b] => Some((r, g, b)),
+        _ => None,
+    }
+}
+
+// Indicator Stickynotes: one JSON file with `notes` and the `categories`
+// that give them their colors.
+fn stickynotes(text: &str) -> Result<Vec<Pin>, String> {
+    let doc: Value = serde_json::from_str(text).map_err(|e| format!("not an Indicator Stickynotes file ({e})"))?;
+    let notes = doc["notes"].as_array().ok_or("not an Indicator Stickynotes file (no notes)")?;
+
+    let mut pins = Vec::new();
+    for note in notes {
+        let (title, content) = pin::split_text(note["body"].as_str().unwrap_or_default());
+        let mut pin = Pin::new(title, content, None);
+        if let Some(id) = note["uuid"].as_str() {
+            pin.id = id.to_string();
+        }
+
+        let props = &note["properties"];
+        if let Some([w, h]) = props["size"].as_array().map(Vec::as_slice) {
+            if let (Some(w), Some(h)) = (w.as_f64(), h.as_f64()) {
+                pin.size = Some((w as f32, h as f32));
+            }
+        }
+        pin.is_locked = props["locked"].as_bool().unwrap_or(false);
+
+        let hsv = note["cat"].as_str().and_then(|cat| doc["categories"][cat]["bgcolor_hsv"].as_array());
+        if let Some([h, s, v]) = hsv.map(Vec::as_slice) {
+            if let (Some(h), Some(s), Some(v)) = (h.as_f64(), s.as_f64(), v.as_f64()) {
+                let (r, g, b) = hsv_to_rgb(h, s, v);
+                pin.color_idx = palette::current().nearest(r, g, b);
+            }
+        }
+
+        // Written in local time without an offset.
+        let modified = note["last_modified"]
+            .as_str()
+            .and_then(|t| NaiveDateTime::parse_from_str(t, "%Y-%m-%dT%H:%M:%S").ok())
+            .and_then(|t| Local.from_local_datetime(&t).earliest());
+        if let Some(t) = modified {
+            pin.created_at = t.with_timezone(&Utc);
+            pin.modified_at = pin.created_at;
+        }
+        pins.push(pin);
+    }
+    Ok(pins)
+}
+
+fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
+    let h = (h.rem_euclid(1.0)) * 6.0;
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    let byte = |f: f64| ((f + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (byte(r), byte(g), byte(b))
+}
+
+fn tomboy_dir(dir: &Path) -> Result<Vec<Pin>, String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "note"))
+        .collect();
+    if files.is_empty() {
+        return Err(format!("no .note files in {}", dir.display()));
+    }
+    files.sort();
+
+    let mut pins = Vec::new();
+    for path in files {
+        let id = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string();
+        match fs::read_to_string(&path) {
+            Ok(xml) => pins.extend(tomboy(&xml, &id)),
+            Err(e) => eprintln!("Skipping {}: {e}", path.display()),
+        }
+    }
+    Ok(pins)
+}
+
+// Tomboy/Gnote: one `.note` XML file per note. The note text repeats the
+// title on its first line and may carry formatting tags, which are dropped.
+fn tomboy(xml: &str, id: &str) -> Option<Pin> {
+    if xml.contains("<tag>system:template</tag>") {
+        return None;
+    }
+    let body = element(xml, "note-content")?;
+    let text = unescape(&strip_tags(body));
+    let (first, content) = pin::split_text(&text);
+    let title = element(xml, "title").map(unescape).unwrap_or(first);
+
+    let mut pin = Pin::new(title, content, None);
+    if !id.is_empty() {
+        pin.id = id.to_string();
+    }
+    let number = |tag| element(xml, tag).and_then(|v| v.trim().parse::<f32>().ok());
+    if let (Some(w), Some(h)) = (number("width"), number("height")) {
+        pin.size = Some((w, h));
+    }
+    let date = |tag| element(xml, tag).and_then(|v| DateTime::parse_from_rfc3339(v.trim()).ok()).map(|t| t.with_timezone(&Utc));
+    if let Some(t) = date("create-date") {
+        pin.created_at = t;
+    }
+    if let Some(t) = date("last-change-date") {
+        pin.modified_at = t;
+    }
+    Some(pin)
+}
+
+/// Inner text of the first `<tag ...>...</tag>`.
+fn element<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}");
+    let mut from = 0;
+    let start = loop {
+        let at = from + xml[from..].find(&open)?;
+        let after = &xml[at + open.len()..];
+        // `<text` must not match `<text-color>`.
+        if after.starts_with('>') || after.starts_with(char::is_whitespace) {
+            break at + open.len() + after.find('>')? + 1;
+        }
+        from = at + open.len();
+    };
+    let end = start + xml[start..].find(&format!("</{tag}>"))?;
+    Some(&xml[start..end])
+}
+
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Decodes XML's named entities and numeric `&#NN;` / `&#xHH;` references.
+/// Anything else is left as written.
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        match rest.find(';').and_then(|end| Some((entity(&rest[1..end])?, end))) {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn entity(name: &str) -> Option<char> {
+    match name {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "amp" => Some('&'),
+        _ => {
+            let number = name.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_color_form() {
+        assert_eq!(parse_rgb("#FFF7D1"), Some((255, 247, 209)));
+        assert_eq!(parse_rgb(" rgb(12, 34, 56) "), Some((12, 34, 56)));
+        assert_eq!(parse_rgb("rgba(12,34,56,0.5)"), Some((12, 34, 56)));
+        assert_eq!(parse_rgb("rgb:ffff/8080/0"), Some((255, 128, 0)));
+        assert_eq!(parse_rgb("rgb:f/80/000"), Some((255, 128, 0)));
+        assert_eq!(parse_rgb("65535 32896 0"), Some((255, 128, 0)));
+    }
+
+    #[test]
+    fn rejects_broken_colors() {
+        for value in ["", "#FFF", "#aé1234", "rgb(1, 2)", "rgb(1, 2, 300)", "rgb:ff/ff", "rgb://", "rgb:000000000/0/0", "1 2"] {
+            assert_eq!(parse_rgb(value), None, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn converts_hsv() {
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0), (255, 0, 0));
+        assert_eq!(hsv_to_rgb(1.0 / 3.0, 1.0, 1.0), (0, 255, 0));
+        assert_eq!(hsv_to_rgb(0.5, 0.0, 0.5), (128, 128, 128));
+    }
+
+    #[test]
+    fn decodes_entities() {
+        assert_eq!(unescape("a &lt;b&gt; &amp; &quot;c&quot; &apos;d&apos;"), "a <b> & \"c\" 'd'");
+        assert_eq!(unescape("caf&#233; &#x2713; &#X41;"), "café ✓ A");
+        assert_eq!(unescape("&amp;lt; stays escaped"), "&lt; stays escaped");
+        assert_eq!(unescape("Tom & Jerry; &nbsp; &#xD800; &#; &"), "Tom & Jerry; &nbsp; &#xD800; &#; &");
+    }
+
+    #[test]
+    fn reads_a_tomboy_note() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<note version="0.3" xmlns="http://beatniksoftware.com/tomboy">
+  <title>Caf&#233; &amp; more</title>
+  <text xml:space="preserve"><note-content version="0.1">Caf&#233; &amp; more
+Buy <bold>beans</bold> &lt;2kg&gt;</note-content></text>
+  <width>300</width>
+  <height>200</height>
+  <create-date>2026-10-01T09:30:00.0000000+02:00</create-date>
+</note>"#;
+        let pin = tomboy(xml, "abc").unwrap();
+        assert_eq!(pin.id, "abc");
+        assert_eq!(pin.title, "Café & more");
+        assert_eq!(pin.content, "Buy beans <2kg>");
+        assert_eq!(pin.size, Some((300.0, 200.0)));
+        assert_eq!(pin.created_at.to_rfc3339(), "2026-10-01T07:30:00+00:00");
+        assert!(tomboy("<note><tags><tag>system:template</tag></tags><note-content>x</note-content></note>", "t").is_none());
+    }
+}
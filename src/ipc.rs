@@ -0,0 +1,209 @@
+//! Keeps Pin-Board to one process per user. The first instance holds a lock
+//! and listens on a Unix socket (a loopback TCP port elsewhere); later
+//! launches hand their request to it over that and exit. The protocol is one
+//! JSON request and one JSON reply, each on its own line.
+
+use chrono::{DateTime, Local};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use crate::store;
+use transport::{Listener, Stream};
+
+// A reply that takes longer than this means the app is stuck.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    Show,
+    Add {
+        text: String,
+        // When set, `text` is all body instead of being split at its first line.
+        #[serde(default)]
+        title: Option<String>,
+        deadline: Option<DateTime<Local>>,
+        color: Option<usize>,
+        #[serde(default)]
+        monospace: bool,
+    },
+    List,
+    Done { id: String },
+    HideAll,
+    ShowAll,
+}
+
+pub type Reply = Result<String, String>;
+
+/// A request from another launch, answered once the app has handled it.
+pub struct Incoming {
+    request: Request,
+    reply: mpsc::Sender<Reply>,
+}
+
+impl Incoming {
+    pub fn answer(self, handle: impl FnOnce(Request) -> Reply) {
+        let _ = self.reply.send(handle(self.request));
+    }
+}
+
+/// Held by the running instance for as long as it lives.
+pub struct Instance {
+    _lock: File,
+    listener: Listener,
+}
+
+fn runtime_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(store::data_dir)
+}
+
+#[cfg(unix)]
+mod transport {
+    use std::io;
+    use std::path::Path;
+
+    pub use std::os::unix::net::{UnixListener as Listener, UnixStream as Stream};
+
+    const SOCKET: &str = "pin-board.sock";
+
+    pub fn bind(dir: &Path) -> io::Result<Listener> {
+        // Left behind by an instance that did not exit cleanly; the lock says it is gone.
+        let _ = std::fs::remove_file(dir.join(SOCKET));
+        Listener::bind(dir.join(SOCKET))
+    }
+
+    pub fn connect(dir: &Path) -> io::Result<Stream> {
+        Stream::connect(dir.join(SOCKET))
+    }
+
+    // The socket is only reachable by its owner.
+    pub fn check(_reader: &mut impl io::BufRead) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Without Unix sockets, listen on a loopback port. Any local user can reach
+// that, so clients first send a token that only this user can read from the
+// port file.
+#[cfg(not(unix))]
+mod transport {
+    use std::io::{self, Write};
+    use std::net::Ipv4Addr;
+    use std::path::Path;
+    use std::sync::OnceLock;
+
+    pub use std::net::{TcpListener as Listener, TcpStream as Stream};
+
+    const PORT_FILE: &str = "pin-board.port";
+
+    static TOKEN: OnceLock<String> = OnceLock::new();
+
+    pub fn bind(dir: &Path) -> io::Result<Listener> {
+        let listener = Listener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let token = TOKEN.get_or_init(|| uuid::Uuid::new_v4().to_string());
+        crate::store::write_atomic(&dir.join(PORT_FILE), format!("{} {token}", listener.local_addr()?.port()).as_bytes())?;
+        Ok(listener)
+    }
+
+    pub fn connect(dir: &Path) -> io::Result<Stream> {
+        let text = std::fs::read_to_string(dir.join(PORT_FILE))?;
+        let (port, token) = text.trim().split_once(' ').ok_or_else(|| io::Error::other("damaged port file"))?;
+        let stream = Stream::connect((Ipv4Addr::LOCALHOST, port.parse().map_err(io::Error::other)?))?;
+        writeln!(&stream, "{token}")?;
+        Ok(stream)
+    }
+
+    pub fn check(reader: &mut impl io::BufRead) -> io::Result<()> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        match TOKEN.get() {
+            Some(token) if line.trim() == token => Ok(()),
+            _ => Err(io::Error::new(io::ErrorKind::PermissionDenied, "wrong token")),
+        }
+    }
+}
+
+impl Instance {
+    /// Becomes the running instance, or returns `None` if another one already is.
+    pub fn acquire() -> io::Result<Option<Self>> {
+        let dir = runtime_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no runtime directory"))?;
+        fs::create_dir_all(&dir)?;
+
+        let lock = File::create(dir.join("pin-board.lock"))?;
+        match lock.try_lock() {
+            Ok(()) => {}
+            Err(fs::TryLockError::WouldBlock) => return Ok(None),
+            Err(fs::TryLockError::Error(e)) => return Err(e),
+        }
+
+        let listener = transport::bind(&dir)?;
+        Ok(Some(Self { _lock: lock, listener }))
+    }
+
+    /// Serves other launches on a background thread. Requests arrive on the
+    /// returned channel, and `ctx` is woken for each one.
+    pub fn listen(self, ctx: egui::Context) -> mpsc::Receiver<Incoming> {
+        let (tx, rx) = mpsc::channel();
+        let Instance { _lock: lock, listener } = self;
+        std::thread::spawn(move || {
+            let _lock = lock;
+            for stream in listener.incoming().flatten() {
+                if let Err(e) = serve(stream, &tx, &ctx) {
+                    eprintln!("Failed to answer another Pin-Board launch: {e}");
+                }
+            }
+        });
+        rx
+    }
+}
+
+fn serve(stream: Stream, tx: &mpsc::Sender<Incoming>, ctx: &egui::Context) -> io::Result<()> {
+    stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    transport::check(&mut reader)?;
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    let reply = match serde_json::from_str::<Request>(&line) {
+        Ok(request) => {
+            let (reply_tx, reply_rx) = mpsc::channel();
+            let _ = tx.send(Incoming { request, reply: reply_tx });
+            ctx.request_repaint();
+            reply_rx.recv_timeout(REPLY_TIMEOUT).unwrap_or_else(|_| Err("Pin-Board did not respond".to_string()))
+        }
+        Err(e) => Err(format!("bad request: {e}")),
+    };
+    writeln!(&stream, "{}", serde_json::to_string(&reply)?)
+}
+
+/// Sends `request` to the running instance.
+pub fn send(request: &Request) -> io::Result<Reply> {
+    let dir = runtime_dir().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no runtime directory"))?;
+
+    // The other instance may hold the lock but not be listening yet.
+    let mut attempts = 0;
+    let stream = loop {
+        match transport::connect(&dir) {
+            Ok(stream) => break stream,
+            Err(_) if attempts < 20 => {
+                attempts += 1;
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            Err(e) => return Err(e),
+        }
+    };
+    stream.set_read_timeout(Some(REPLY_TIMEOUT * 2))?;
+    writeln!(&stream, "{}", serde_json::to_string(request)?)?;
+
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    serde_json::from_str(&line).map_err(io::Error::other)
+}
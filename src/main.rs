@@ -1,10 +1,66 @@
 mod app;
+mod autosave;
+mod bundle;
+mod cli;
+mod crypto;
+mod folder_sync;
+mod history;
+mod images;
+mod importers;
+mod ipc;
+mod markdown;
+mod markdown_view;
+mod migrate;
+mod monitors;
+mod palette;
 mod pin;
+mod recovery;
+mod settings;
+mod shutdown;
+mod sqlite_store;
+mod store;
+mod timefmt;
 mod timer;
+mod todotxt;
+mod trash;
 use app::AppState;
 use eframe::egui;
 
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if matches!(args.first().map(String::as_str), Some("help" | "-h" | "--help")) {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    let command = match cli::parse(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("Pin-Board: {e}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    // One instance per user: later launches hand their request to it and exit.
+    let instance = match ipc::Instance::acquire() {
+        Ok(Some(instance)) => Some(instance),
+        Ok(None) => {
+            let command = command.unwrap_or(cli::Command { request: ipc::Request::Show, json: false });
+            let reply = ipc::send(&command.request)
+                .unwrap_or_else(|e| Err(format!("Pin-Board is running but did not answer: {e}")));
+            finish(&command, reply);
+        }
+        Err(e) => {
+            eprintln!("Failed to check for a running Pin-Board: {e}");
+            None
+        }
+    };
+    if let Some(command) = command.filter(|c| !matches!(c.request, ipc::Request::Show)) {
+        // `instance` stays held, so the app cannot start while the store is edited.
+        let reply = cli::run_offline(command.request.clone());
+        drop(instance);
+        finish(&command, reply);
+    }
+
     #[cfg(target_os = "linux")]
     {
         if gtk::init().is_err() {
@@ -23,10 +79,18 @@ fn main() -> eframe::Result<()> {
     };
 
     eframe::run_native(
-        "Pin-Board",
+        store::APP_ID,
         native_options,
         Box::new(|cc| {
-            Ok(Box::new(AppState::new(cc)))
+            Ok(Box::new(AppState::new(cc, instance)))
         }),
     )
 }
+
+fn finish(command: &cli::Command, reply: ipc::Reply) -> ! {
+    if let Err(e) = cli::print(command, reply) {
+        eprintln!("Pin-Board: {e}");
+        std::process::exit(1);
+    }
+    std::process::exit(0);
+}
@@ -0,0 +1,311 @@
+//! The board as one Markdown document, for pasting into wikis and back.
+//!
+//! ```text
+//! # Pin-Board
+//!
+//! ## Deploy at 5
+//!
+//! - [ ] done
+//!
+//! ---
+//! id: 6f1c2a9e-...
+//! color: blue
+//! locked: false
+//! deadline: 2026-10-18T17:00:00+02:00
+//! ---
+//!
+//! Check the staging logs first
+//! ```
+
+use chrono::{DateTime, Local, Utc};
+use uuid::Uuid;
+
+use crate::images;
+use crate::palette;
+use crate::pin::Pin;
+
+/// Renders `pins` as one document that `import` reads back losslessly.
+pub fn export(pins: &[Pin]) -> String {
+    let mut out = String::from("# Pin-Board\n");
+    for pin in pins {
+        let title = if pin.title.is_empty() { "Untitled" } else { pin.title.lines().next().unwrap_or("") };
+        out.push_str(&format!("\n## {title}\n\n"));
+        out.push_str(if pin.is_completed { "- [x] done\n\n" } else { "- [ ] done\n\n" });
+        out.push_str(&write_front_matter(&fields(pin)));
+        let body = pin.body_text();
+        if !body.is_empty() {
+            out.push('\n');
+            for line in body.lines() {
+                out.push_str(&escape_line(line));
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// Parses a document written by `export`. Pins without an `id` get a new one.
+pub fn import(text: &str) -> Vec<Pin> {
+    let mut pins = Vec::new();
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let Some(title) = line.strip_prefix("## ") else { continue };
+        let mut section = Vec::new();
+        while let Some(next) = lines.peek() {
+            if next.starts_with("## ") { break; }
+            section.push(lines.next().unwrap_or_default());
+        }
+        pins.push(parse_section(title.trim(), &section));
+    }
+    dedup_ids(&mut pins);
+    pins
+}
+
+fn parse_section(title: &str, lines: &[&str]) -> Pin {
+    let mut pin = Pin::new(if title == "Untitled" { String::new() } else { title.to_string() }, String::new(), None);
+    let mut rest = skip_blank(lines);
+
+    if let Some(first) = rest.first() {
+        let first = first.trim();
+        if first.starts_with("- [x]") || first.starts_with("- [X]") {
+            pin.is_completed = true;
+            rest = skip_blank(&rest[1..]);
+        } else if first.starts_with("- [ ]") {
+            rest = skip_blank(&rest[1..]);
+        }
+    }
+
+    let (meta, body) = read_front_matter(rest);
+    for (key, value) in &meta {
+        apply_field(&mut pin, key, value);
+    }
+    if pin.is_completed && pin.completed_at.is_none() {
+        pin.completed_at = Some(Utc::now());
+    }
+
+    let body: Vec<String> = skip_blank(body).iter().map(|l| unescape_line(l)).collect();
+    pin.set_body_text(body.join("\n").trim_end().to_string());
+    pin
+}
+
+fn skip_blank<'a, 'b>(lines: &'a [&'b str]) -> &'a [&'b str] {
+    let start = lines.iter().position(|l| !l.trim().is_empty()).unwrap_or(lines.len());
+    &lines[start..]
+}
+
+// Body lines that would read as a heading get a backslash, which Markdown
+// renders away and `unescape_line` strips again.
+fn escape_line(line: &str) -> String {
+    if line.starts_with('#') || line.starts_with('\\') { format!("\\{line}") } else { line.to_string() }
+}
+
+fn unescape_line(line: &str) -> String {
+    match line.strip_prefix('\\') {
+        Some(rest) if rest.starts_with('#') || rest.starts_with('\\') => rest.to_string(),
+        _ => line.to_string(),
+    }
+}
+
+/// Pin properties as `key: value` pairs, shared by every text format that
+/// carries front matter.
+pub fn fields(pin: &Pin) -> Vec<(&'static str, String)> {
+    let mut out = vec![
+        ("id", pin.id.clone()),
+        ("color", palette::current().name(pin.color_idx)),
+        ("locked", pin.is_locked.to_string()),
+    ];
+    if let Some(deadline) = pin.deadline {
+        out.push(("deadline", deadline.to_rfc3339()));
+    }
+    out.push(("opacity", format!("{:.2}", pin.opacity)));
+    if let Some((w, h)) = pin.size {
+        out.push(("size", format!("{}x{}", w.round(), h.round())));
+    }
+    out.push(("on_top", pin.is_always_on_top.to_string()));
+    if pin.monospace {
+        out.push(("monospace", "true".to_string()));
+    }
+    if pin.markdown {
+        out.push(("markdown", "true".to_string()));
+    }
+    if !pin.tags.is_empty() {
+        out.push(("tags", pin.tags.join(", ")));
+    }
+    if let Some(name) = &pin.image {
+        out.push(("image", name.clone()));
+    }
+    // The body is then one `- [ ] item` line per item.
+    if pin.checklist.is_some() {
+        out.push(("checklist", "true".to_string()));
+        out.push(("auto_complete", pin.auto_complete.to_string()));
+    }
+    out.push(("created", pin.created_at.to_rfc3339()));
+    out.push(("modified", pin.modified_at.to_rfc3339()));
+    if let Some(done) = pin.completed_at {
+        out.push(("completed", done.to_rfc3339()));
+    }
+    out
+}
+
+/// Sets one front matter field on `pin`. Unknown keys and bad values are ignored.
+pub fn apply_field(pin: &mut Pin, key: &str, value: &str) {
+    let value = value.trim();
+    let bool_value = || match value {
+        "true" | "yes" => Some(true),
+        "false" | "no" => Some(false),
+        _ => None,
+    };
+    let utc = || DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc));
+
+    match key {
+        "id" if !value.is_empty() => pin.id = value.to_string(),
+        "color" => {
+            if let Some(idx) = palette::current().parse(value) { pin.color_idx = idx; }
+        }
+        "locked" => {
+            if let Some(b) = bool_value() { pin.is_locked = b; }
+        }
+        "deadline" => {
+            pin.deadline = DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Local));
+        }
+        "opacity" => {
+            if let Ok(o) = value.parse::<f32>() { pin.opacity = o.clamp(0.2, 1.0); }
+        }
+        "size" => {
+            let parsed = value.split_once('x').and_then(|(w, h)| Some((w.trim().parse().ok()?, h.trim().parse().ok()?)));
+            if let Some(size) = parsed { pin.size = Some(size); }
+        }
+        "on_top" => {
+            if let Some(b) = bool_value() { pin.is_always_on_top = b; }
+        }
+        "monospace" => {
+            if let Some(b) = bool_value() { pin.monospace = b; }
+        }
+        "markdown" => {
+            if let Some(b) = bool_value() { pin.markdown = b; }
+        }
+        "tags" => {
+            pin.tags.clear();
+            for tag in value.split(',') { pin.add_tag(tag); }
+        }
+        // A file in the images directory, never a path.
+        "image" => pin.image = Some(value.to_string()).filter(|v| images::is_plain_name(v)),
+        "checklist" => match bool_value() {
+            Some(true) => { pin.checklist.get_or_insert_with(Vec::new); }
+            Some(false) => pin.checklist = None,
+            None => {}
+        },
+        "auto_complete" => {
+            if let Some(b) = bool_value() { pin.auto_complete = b; }
+        }
+        "created" => {
+            if let Some(t) = utc() { pin.created_at = t; }
+        }
+        "modified" => {
+            if let Some(t) = utc() { pin.modified_at = t; }
+        }
+        "completed" => pin.completed_at = utc(),
+        _ => {}
+    }
+}
+
+pub fn write_front_matter(fields: &[(&str, String)]) -> String {
+    let mut out = String::from("---\n");
+    for (key, value) in fields {
+        out.push_str(&format!("{key}: {value}\n"));
+    }
+    out.push_str("---\n");
+    out
+}
+
+/// Splits a leading `---` block off `lines`, returning its pairs and the rest.
+pub fn read_front_matter<'a, 'b>(lines: &'a [&'b str]) -> (Vec<(String, String)>, &'a [&'b str]) {
+    if lines.first().map(|l| l.trim()) != Some("---") {
+        return (Vec::new(), lines);
+    }
+    let Some(end) = lines[1..].iter().position(|l| l.trim() == "---") else {
+        return (Vec::new(), lines);
+    };
+    let pairs = lines[1..=end]
+        .iter()
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+    (pairs, &lines[end + 2..])
+}
+
+// A document pasted together from two exports can repeat an id.
+fn dedup_ids(pins: &mut [Pin]) {
+    let mut seen = std::collections::HashSet::new();
+    for pin in pins {
+        if !seen.insert(pin.id.clone()) {
+            pin.id = Uuid::new_v4().to_string();
+            seen.insert(pin.id.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pin::ChecklistItem;
+
+    #[test]
+    fn export_then_import_keeps_every_field() {
+        let mut note = Pin::new("Deploy at 5".into(), "# not a heading\n\\ kept\nCheck the logs".into(), None);
+        note.color_idx = 1;
+        note.is_locked = true;
+        note.deadline = Local::now().checked_add_signed(chrono::Duration::hours(3));
+        note.opacity = 0.5;
+        note.size = Some((320.0, 240.0));
+        note.markdown = true;
+        note.add_tag("release");
+        note.add_tag("@work");
+        note.image = Some("a.png".into());
+        note.set_completed(true);
+
+        let mut list = Pin::new(String::new(), String::new(), None);
+        list.checklist = Some(vec![
+            ChecklistItem { text: "milk".into(), done: true },
+            ChecklistItem { text: "eggs".into(), done: false },
+        ]);
+        list.auto_complete = true;
+        list.monospace = true;
+
+        let pins = vec![note, list];
+        let back = import(&export(&pins));
+        assert_eq!(back.len(), 2);
+        for (a, b) in pins.iter().zip(&back) {
+            assert_eq!(a.id, b.id);
+            assert_eq!(a.body_text(), b.body_text());
+            assert_eq!(a.color_idx, b.color_idx);
+            assert_eq!(a.is_locked, b.is_locked);
+            assert_eq!(a.deadline, b.deadline);
+            assert_eq!(a.opacity, b.opacity);
+            assert_eq!(a.size, b.size);
+            assert_eq!(a.is_always_on_top, b.is_always_on_top);
+            assert_eq!(a.monospace, b.monospace);
+            assert_eq!(a.markdown, b.markdown);
+            assert_eq!(a.tags, b.tags);
+            assert_eq!(a.image, b.image);
+            assert_eq!(a.auto_complete, b.auto_complete);
+            assert_eq!(a.is_completed, b.is_completed);
+            assert_eq!(a.created_at, b.created_at);
+            assert_eq!(a.modified_at, b.modified_at);
+            assert_eq!(a.completed_at, b.completed_at);
+        }
+        assert_eq!(back[0].title, "Deploy at 5");
+        assert_eq!(back[0].content, pins[0].content);
+        assert_eq!(back[1].checklist.as_ref().map(Vec::len), Some(2));
+    }
+
+    #[test]
+    fn repeated_ids_get_new_ones() {
+        let pin = Pin::new("Twice".into(), String::new(), None);
+        let doc = export(&[pin.clone(), pin]);
+        let back = import(&doc);
+        assert_eq!(back.len(), 2);
+        assert_ne!(back[0].id, back[1].id);
+    }
+}
@@ -0,0 +1,238 @@
+//! Draws pin text as Markdown: headings, bold/italic, lists, inline code,
+//! code blocks, quotes and links. (`markdown.rs` is the board export format.)
+
+use eframe::egui;
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+#[derive(Default, Clone)]
+struct Span {
+    text: String,
+    strong: bool,
+    italic: bool,
+    strike: bool,
+    code: bool,
+    link: Option<String>,
+}
+
+enum Block {
+    Text {
+        spans: Vec<Span>,
+        prefix: Option<String>,
+        heading: Option<u8>,
+        indent: usize,
+        quote: bool,
+        // Space below, between paragraphs but not list items.
+        gap: bool,
+    },
+    Code(String),
+    Rule,
+}
+
+#[derive(Default)]
+struct Builder {
+    blocks: Vec<Block>,
+    spans: Vec<Span>,
+    prefix: Option<String>,
+    heading: Option<u8>,
+    lists: Vec<Option<u64>>,
+    quote: usize,
+    strong: usize,
+    italic: usize,
+    strike: usize,
+    link: Option<String>,
+    code_block: Option<String>,
+}
+
+impl Builder {
+    fn span(&mut self, text: &str, code: bool) {
+        self.spans.push(Span {
+            text: text.to_string(),
+            strong: self.strong > 0 || self.heading.is_some(),
+            italic: self.italic > 0,
+            strike: self.strike > 0,
+            code,
+            link: self.link.clone(),
+        });
+    }
+
+    fn flush(&mut self, gap: bool) {
+        if self.spans.is_empty() && self.prefix.is_none() { return; }
+        self.blocks.push(Block::Text {
+            spans: std::mem::take(&mut self.spans),
+            prefix: self.prefix.take(),
+            heading: self.heading,
+            indent: self.lists.len().saturating_sub(1) + self.quote,
+            quote: self.quote > 0,
+            gap,
+        });
+    }
+
+    fn event(&mut self, event: Event) {
+        if let Some(code) = &mut self.code_block {
+            match event {
+                Event::Text(text) => code.push_str(&text),
+                Event::End(TagEnd::CodeBlock) => {
+                    let code = self.code_block.take().unwrap_or_default();
+                    self.blocks.push(Block::Code(code.trim_end_matches('\n').to_string()));
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                self.flush(false);
+                self.heading = Some(level as u8);
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                self.flush(true);
+                self.heading = None;
+            }
+            Event::End(TagEnd::Paragraph) => self.flush(self.lists.is_empty()),
+            Event::Start(Tag::BlockQuote(_)) => {
+                self.flush(false);
+                self.quote += 1;
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                self.flush(true);
+                self.quote = self.quote.saturating_sub(1);
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                self.flush(false);
+                self.code_block = Some(String::new());
+            }
+            Event::Start(Tag::List(start)) => {
+                self.flush(false);
+                self.lists.push(start);
+            }
+            Event::End(TagEnd::List(_)) => {
+                self.flush(false);
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    if let Some(Block::Text { gap, .. }) = self.blocks.last_mut() { *gap = true; }
+                }
+            }
+            Event::Start(Tag::Item) => {
+                self.flush(false);
+                self.prefix = Some(match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}. ", *n - 1)
+                    }
+                    _ => "• ".to_string(),
+                });
+            }
+            Event::End(TagEnd::Item) => self.flush(false),
+            Event::TaskListMarker(done) => self.prefix = Some(if done { "✓ " } else { "○ " }.to_string()),
+            Event::Start(Tag::Emphasis) => self.italic += 1,
+            Event::End(TagEnd::Emphasis) => self.italic = self.italic.saturating_sub(1),
+            Event::Start(Tag::Strong) => self.strong += 1,
+            Event::End(TagEnd::Strong) => self.strong = self.strong.saturating_sub(1),
+            Event::Start(Tag::Strikethrough) => self.strike += 1,
+            Event::End(TagEnd::Strikethrough) => self.strike = self.strike.saturating_sub(1),
+            Event::Start(Tag::Link { dest_url, .. }) | Event::Start(Tag::Image { dest_url, .. }) => {
+                self.link = Some(dest_url.to_string());
+            }
+            Event::End(TagEnd::Link) | Event::End(TagEnd::Image) => self.link = None,
+            Event::Text(text) => self.span(&text, false),
+            Event::Code(text) => self.span(&text, true),
+            Event::Html(text) | Event::InlineHtml(text) => self.span(text.trim_end_matches('\n'), false),
+            Event::SoftBreak => self.span(" ", false),
+            Event::HardBreak => self.flush(false),
+            Event::Rule => {
+                self.flush(false);
+                self.blocks.push(Block::Rule);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn parse(text: &str) -> Vec<Block> {
+    let mut builder = Builder::default();
+    for event in Parser::new_ext(text, Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS) {
+        builder.event(event);
+    }
+    builder.flush(false);
+    builder.blocks
+}
+
+const LINK_COLOR: egui::Color32 = egui::Color32::from_rgb(30, 90, 200);
+
+/// Draws `text` in `color` at body size `size`, and returns the area it took.
+pub fn show(ui: &mut egui::Ui, text: &str, color: egui::Color32, size: f32) -> egui::Response {
+    ui.vertical(|ui| {
+        for block in parse(text) {
+            match block {
+                Block::Text { spans, prefix, heading, indent, quote, gap } => {
+                    let size = match heading {
+                        Some(1) => size * 1.4,
+                        Some(2) => size * 1.25,
+                        Some(_) => size * 1.1,
+                        None => size,
+                    };
+                    let color = if quote { color.gamma_multiply(0.7) } else { color };
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing.x = 0.0;
+                        ui.add_space(indent as f32 * 14.0);
+                        if let Some(prefix) = prefix {
+                            ui.label(egui::RichText::new(prefix).size(size).color(color));
+                        }
+                        ui.horizontal_wrapped(|ui| {
+                            ui.spacing_mut().item_spacing.x = 0.0;
+                            for span in spans {
+                                show_span(ui, span, color, size);
+                            }
+                        });
+                    });
+                    if gap { ui.add_space(size * 0.4); }
+                }
+                Block::Code(code) => {
+                    egui::Frame::none()
+                        .fill(egui::Color32::from_black_alpha(20))
+                        .rounding(4.0)
+                        .inner_margin(6.0)
+                        .show(ui, |ui| {
+                            ui.set_width(ui.available_width());
+                            ui.label(egui::RichText::new(code).monospace().size(size * 0.8).color(color));
+                        });
+                    ui.add_space(size * 0.4);
+                }
+                Block::Rule => {
+                    ui.separator();
+                }
+            }
+        }
+    })
+    .response
+}
+
+fn show_span(ui: &mut egui::Ui, span: Span, color: egui::Color32, size: f32) {
+    let mut text = egui::RichText::new(span.text).size(size);
+    if span.code {
+        text = text.code().size(size * 0.85);
+    }
+    if span.italic { text = text.italics(); }
+    if span.strike { text = text.strikethrough(); }
+    match span.link {
+        Some(url) => {
+            ui.hyperlink_to(text.color(LINK_COLOR).underline(), url);
+        }
+        None if span.strong => bold_label(ui, text, color),
+        None => {
+            ui.label(text.color(color));
+        }
+    }
+}
+
+// egui's fonts have no bold face, so strong text is drawn twice, the second
+// time one pixel to the right, which thickens every stroke.
+fn bold_label(ui: &mut egui::Ui, text: egui::RichText, color: egui::Color32) {
+    let (pos, galley, response) = egui::Label::new(text.color(color)).selectable(false).layout_in_ui(ui);
+    if ui.is_rect_visible(response.rect) {
+        let pixel = 1.0 / ui.ctx().pixels_per_point();
+        ui.painter().galley(pos, galley.clone(), color);
+        ui.painter().galley(pos + egui::vec2(pixel, 0.0), galley, color);
+    }
+}
@@ -0,0 +1,198 @@
+use chrono::{Local, NaiveTime, Utc};
+use serde_json::{json, Value};
+use std::fmt;
+
+/// Version written by this build. Bump it together with a new entry in `MIGRATIONS`.
+pub const SCHEMA_VERSION: u64 = 2;
+
+// MIGRATIONS[n] upgrades a version `n` document to version `n + 1`.
+const MIGRATIONS: &[fn(&mut Value)] = &[v0_to_v1, v1_to_v2];
+
+#[derive(Debug)]
+pub enum MigrationError {
+    NotAnObject,
+    TooNew(u64),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::NotAnObject => write!(f, "stored state is not a JSON object"),
+            MigrationError::TooNew(v) => write!(
+                f,
+                "stored state has schema version {v}, this build only understands up to {SCHEMA_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+/// Upgrades `doc` in place to `SCHEMA_VERSION`, one step at a time.
+pub fn migrate(doc: &mut Value) -> Result<(), MigrationError> {
+    let version = schema_version(doc)?;
+    if version > SCHEMA_VERSION {
+        return Err(MigrationError::TooNew(version));
+    }
+    for step in &MIGRATIONS[version as usize..] {
+        step(doc);
+    }
+    doc["schema_version"] = json!(SCHEMA_VERSION);
+    Ok(())
+}
+
+fn schema_version(doc: &Value) -> Result<u64, MigrationError> {
+    let obj = doc.as_object().ok_or(MigrationError::NotAnObject)?;
+    // Releases up to 0.2.0 wrote no version at all.
+    Ok(obj.get("schema_version").and_then(Value::as_u64).unwrap_or(0))
+}
+
+// Visits pins on the board, in the archive and in the trash.
+fn for_each_pin(doc: &mut Value, mut f: impl FnMut(&mut serde_json::Map<String, Value>)) {
+    for key in ["pins", "archive"] {
+        if let Some(pins) = doc.get_mut(key).and_then(Value::as_array_mut) {
+            pins.iter_mut().filter_map(Value::as_object_mut).for_each(&mut f);
+        }
+    }
+    if let Some(trash) = doc.get_mut("trash").and_then(Value::as_array_mut) {
+        trash.iter_mut().filter_map(|t| t.get_mut("pin")).filter_map(Value::as_object_mut).for_each(&mut f);
+    }
+}
+
+// v0: 0.1.x boards lacked `title`, `opacity`, `is_locked` and
+// `is_always_on_top`, and some 0.2.0 boards saved `opacity` as 0.0.
+fn v0_to_v1(doc: &mut Value) {
+    if doc.get("pins").is_none() {
+        doc["pins"] = json!([]);
+    }
+    if doc.get("global_timer").is_none() {
+        doc["global_timer"] = json!({ "duration_secs": 0, "remaining_secs": 0, "is_running": false });
+    }
+    for_each_pin(doc, |pin| {
+        pin.entry("title").or_insert(json!(""));
+        pin.entry("content").or_insert(json!(""));
+        pin.entry("color_idx").or_insert(json!(0));
+        pin.entry("visible").or_insert(json!(true));
+        pin.entry("is_locked").or_insert(json!(false));
+        pin.entry("is_always_on_top").or_insert(json!(true));
+        let opacity = pin.get("opacity").and_then(Value::as_f64).unwrap_or(0.0);
+        if opacity <= 0.0 {
+            pin.insert("opacity".into(), json!(0.95));
+        }
+    });
+}
+
+// v1: `created_at` was a display string like "03:15 PM" with no date, and
+// there was no `modified_at`. The date is lost, so we assume the most recent
+// day on which that time has already passed.
+fn v1_to_v2(doc: &mut Value) {
+    let now = Local::now();
+    for_each_pin(doc, |pin| {
+        let old = pin.get("created_at").and_then(Value::as_str).unwrap_or("");
+        let created = NaiveTime::parse_from_str(old.trim(), "%I:%M %p")
+            .ok()
+            .and_then(|time| now.date_naive().and_time(time).and_local_timezone(Local).earliest())
+            .map(|t| if t > now { t - chrono::Duration::days(1) } else { t })
+            .unwrap_or(now)
+            .with_timezone(&Utc);
+        pin.insert("created_at".into(), json!(created));
+        pin.entry("modified_at").or_insert(json!(created));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::AppState;
+    use chrono::DateTime;
+
+    const V0_1: &str = include_str!("../tests/fixtures/v0_1.json");
+    const V1: &str = include_str!("../tests/fixtures/v1.json");
+
+    fn doc(text: &str) -> Value {
+        serde_json::from_str(text).unwrap()
+    }
+
+    fn all_pins(doc: &mut Value) -> Vec<Value> {
+        let mut pins = Vec::new();
+        for_each_pin(doc, |pin| pins.push(Value::Object(pin.clone())));
+        pins
+    }
+
+    #[test]
+    fn v0_to_v1_fills_in_defaults() {
+        let mut doc = doc(V0_1);
+        v0_to_v1(&mut doc);
+        let pins = doc["pins"].as_array().unwrap();
+        assert_eq!(pins[0]["id"], "0a4f7c1e-5b2d-4c8e-9f31-2d6b8e0a1c55");
+        assert_eq!(pins[0]["content"], "Call the dentist");
+        assert_eq!(pins[0]["title"], "");
+        assert_eq!(pins[0]["opacity"], 0.95);
+        assert_eq!(pins[0]["is_always_on_top"], true);
+        assert_eq!(pins[0]["is_locked"], false);
+        assert_eq!(pins[0]["color_idx"], 2);
+        // Saved as 0.0 by some 0.2.0 builds.
+        assert_eq!(pins[1]["opacity"], 0.95);
+        assert_eq!(pins[1]["content"], "Ship 0.1\nTag the release first");
+        assert_eq!(doc["global_timer"]["duration_secs"], 1500);
+    }
+
+    #[test]
+    fn v1_to_v2_dates_every_pin() {
+        let mut doc = doc(V1);
+        v1_to_v2(&mut doc);
+        let pins = all_pins(&mut doc);
+        assert_eq!(pins.len(), 3, "board, archive and trash");
+        for pin in &pins {
+            let created = pin["created_at"].as_str().unwrap();
+            let created = DateTime::parse_from_rfc3339(created).unwrap();
+            assert!(created <= Utc::now());
+            assert_eq!(pin["modified_at"], pin["created_at"]);
+        }
+        assert_eq!(pins[1]["id"], "c4d8a1f2-9e6b-4b0a-8c37-5f2e7d1a9b63");
+        assert_eq!(pins[2]["content"], "Deleted by mistake");
+        assert_eq!(doc["trash"][0]["deleted_at"], "2026-10-01T12:00:00Z");
+    }
+
+    #[test]
+    fn v1_keeps_settings_a_user_made() {
+        let mut doc = doc(V1);
+        migrate(&mut doc).unwrap();
+        let pin = &doc["pins"][0];
+        assert_eq!(pin["title"], "Standup");
+        assert_eq!(pin["opacity"], 0.8);
+        assert_eq!(pin["is_locked"], true);
+        assert_eq!(pin["is_always_on_top"], false);
+    }
+
+    #[test]
+    fn old_releases_load_as_the_current_board() {
+        for fixture in [V0_1, V1] {
+            let before: Vec<_> = all_pins(&mut doc(fixture)).iter().map(|p| (p["id"].clone(), p["content"].clone())).collect();
+            let mut migrated = doc(fixture);
+            migrate(&mut migrated).unwrap();
+            assert_eq!(migrated["schema_version"], SCHEMA_VERSION);
+            let state: AppState = serde_json::from_value(migrated).unwrap();
+            let pins = state.pins.iter().chain(&state.archive).chain(state.trash.iter().map(|t| &t.pin));
+            let after: Vec<_> = pins.map(|p| (json!(p.id), json!(p.content))).collect();
+            assert_eq!(before, after);
+        }
+    }
+
+    #[test]
+    fn current_documents_are_left_alone() {
+        let mut doc = doc(V1);
+        migrate(&mut doc).unwrap();
+        let migrated = doc.clone();
+        migrate(&mut doc).unwrap();
+        assert_eq!(doc, migrated);
+    }
+
+    #[test]
+    fn rejects_future_and_malformed_documents() {
+        let mut doc = json!({ "schema_version": SCHEMA_VERSION + 1, "pins": [] });
+        assert!(matches!(migrate(&mut doc), Err(MigrationError::TooNew(v)) if v == SCHEMA_VERSION + 1));
+        assert_eq!(doc["schema_version"], SCHEMA_VERSION + 1, "left untouched");
+        assert!(matches!(migrate(&mut json!([])), Err(MigrationError::NotAnObject)));
+    }
+}
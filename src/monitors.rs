@@ -0,0 +1,76 @@
+//! Where the connected monitors are, for putting pins back where they were.
+
+use eframe::egui;
+
+/// Each connected monitor's area in points, the primary one first. Empty
+/// where this cannot be found out.
+#[cfg(target_os = "linux")]
+pub fn areas() -> Vec<egui::Rect> {
+    use gtk::gdk::prelude::MonitorExt;
+
+    let Some(display) = gtk::gdk::Display::default() else { return Vec::new() };
+    let mut monitors: Vec<_> = (0..display.n_monitors()).filter_map(|i| display.monitor(i)).collect();
+    monitors.sort_by_key(|m| !m.is_primary());
+    monitors
+        .iter()
+        .map(|m| {
+            let g = m.geometry();
+            egui::Rect::from_min_size(egui::pos2(g.x() as f32, g.y() as f32), egui::vec2(g.width() as f32, g.height() as f32))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn areas() -> Vec<egui::Rect> {
+    Vec::new()
+}
+
+/// Where a window saved at `pos` should open: there, if that is still on a
+/// monitor, otherwise moved onto the primary one. Without a list of
+/// monitors, `fallback` is the size of the dashboard's monitor.
+pub fn place(pos: egui::Pos2, size: egui::Vec2, monitors: &[egui::Rect], fallback: Option<egui::Vec2>) -> egui::Pos2 {
+    if monitors.iter().any(|m| m.contains(pos)) {
+        return pos;
+    }
+    let screen = match (monitors.first(), fallback) {
+        (Some(primary), _) => *primary,
+        (None, Some(size)) => egui::Rect::from_min_size(egui::Pos2::ZERO, size),
+        (None, None) => return pos,
+    };
+    let max = (screen.max - size).max(screen.min);
+    pos.clamp(screen.min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> egui::Rect {
+        egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(w, h))
+    }
+
+    const SIZE: egui::Vec2 = egui::vec2(280.0, 240.0);
+
+    #[test]
+    fn leaves_pins_on_any_monitor_alone() {
+        // A second monitor to the left of the primary one.
+        let monitors = [rect(0.0, 0.0, 1920.0, 1080.0), rect(-1280.0, 0.0, 1280.0, 1024.0)];
+        for pos in [egui::pos2(100.0, 100.0), egui::pos2(-1000.0, 500.0), egui::pos2(1900.0, 1000.0)] {
+            assert_eq!(place(pos, SIZE, &monitors, None), pos);
+        }
+    }
+
+    #[test]
+    fn moves_pins_off_every_monitor_onto_the_primary_one() {
+        let monitors = [rect(1920.0, 0.0, 1920.0, 1080.0), rect(0.0, 0.0, 1920.0, 1080.0)];
+        assert_eq!(place(egui::pos2(5000.0, 300.0), SIZE, &monitors, None), egui::pos2(3840.0 - 280.0, 300.0));
+        assert_eq!(place(egui::pos2(-900.0, -50.0), SIZE, &monitors, None), egui::pos2(1920.0, 0.0));
+    }
+
+    #[test]
+    fn falls_back_to_the_dashboard_monitor() {
+        let fallback = Some(egui::vec2(1920.0, 1080.0));
+        assert_eq!(place(egui::pos2(3000.0, 2000.0), SIZE, &[], fallback), egui::pos2(1640.0, 840.0));
+        assert_eq!(place(egui::pos2(3000.0, 2000.0), SIZE, &[], None), egui::pos2(3000.0, 2000.0));
+    }
+}
@@ -0,0 +1,254 @@
+//! The pin colors. Pins keep an index into the palette, which lives in
+//! `$XDG_CONFIG_HOME/pin-board/palette.json` so the command line sees the
+//! same names as the app:
+//!
+//! ```text
+//! { "colors": [ { "name": "Yellow", "rgb": [255, 235, 156], "meaning": "Ideas" }, ... ],
+//!   "presets": [ { "name": "Team", "colors": [ ... ] } ] }
+//! ```
+//!
+//! `presets` are extra palettes offered next to the built-in ones.
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use crate::store;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct PaletteColor {
+    pub name: String,
+    pub rgb: [u8; 3],
+    /// What the color stands for, e.g. "Meetings".
+    #[serde(default)]
+    pub meaning: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Palette {
+    pub colors: Vec<PaletteColor>,
+    #[serde(default)]
+    pub presets: Vec<Preset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    pub colors: Vec<PaletteColor>,
+}
+
+fn color(name: &str, r: u8, g: u8, b: u8) -> PaletteColor {
+    PaletteColor { name: name.to_string(), rgb: [r, g, b], meaning: String::new() }
+}
+
+/// The built-in palettes, the first of which is the default.
+pub fn builtin() -> Vec<Preset> {
+    vec![
+        Preset {
+            name: "Classic".to_string(),
+            colors: vec![
+                color("Yellow", 255, 235, 156),
+                color("Blue", 186, 237, 255),
+                color("Pink", 255, 179, 186),
+                color("Green", 179, 255, 196),
+                color("Lavender", 222, 186, 255),
+                color("Peach", 255, 218, 186),
+            ],
+        },
+        // Light tints of the Okabe-Ito colors, which stay apart under the
+        // common kinds of color blindness.
+        Preset {
+            name: "Colorblind safe".to_string(),
+            colors: vec![
+                color("Orange", 240, 190, 110),
+                color("Sky blue", 150, 205, 240),
+                color("Bluish green", 110, 200, 170),
+                color("Yellow", 245, 235, 120),
+                color("Blue", 120, 170, 220),
+                color("Vermillion", 235, 150, 110),
+                color("Reddish purple", 215, 160, 190),
+            ],
+        },
+        Preset {
+            name: "High contrast".to_string(),
+            colors: vec![
+                color("White", 255, 255, 255),
+                color("Yellow", 255, 230, 0),
+                color("Cyan", 0, 230, 255),
+                color("Lime", 120, 255, 80),
+                color("Orange", 255, 160, 40),
+                color("Magenta", 255, 120, 220),
+            ],
+        },
+    ]
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self { colors: builtin().swap_remove(0).colors, presets: Vec::new() }
+    }
+}
+
+impl Palette {
+    pub fn path() -> Option<PathBuf> {
+        store::config_dir().map(|d| d.join("palette.json"))
+    }
+
+    /// The saved palette, or the default one if there is none yet.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else { return Self::default() };
+        match std::fs::read_to_string(&path) {
+            Ok(text) => match Self::from_json(&text) {
+                Ok(palette) => palette,
+                Err(e) => {
+                    eprintln!("Failed to read {}: {e}", path.display());
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    // Palettes and presets without colors are left out.
+    fn from_json(text: &str) -> serde_json::Result<Self> {
+        let mut palette: Palette = serde_json::from_str(text)?;
+        palette.presets.retain(|p| !p.colors.is_empty());
+        if palette.colors.is_empty() {
+            palette.colors = Self::default().colors;
+        }
+        Ok(palette)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        let text = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        store::write_atomic(&path, text.as_bytes())
+    }
+
+    // `None` only for a palette with no colors, which `load` never returns.
+    fn entry(&self, idx: usize) -> Option<&PaletteColor> {
+        self.colors.get(idx.checked_rem(self.colors.len())?)
+    }
+
+    pub fn color(&self, idx: usize) -> egui::Color32 {
+        let [r, g, b] = self.entry(idx).map_or([255, 235, 156], |c| c.rgb);
+        egui::Color32::from_rgb(r, g, b)
+    }
+
+    /// The name text formats write for a color.
+    pub fn name(&self, idx: usize) -> String {
+        self.colors.get(idx).map_or_else(|| idx.to_string(), |c| c.name.to_lowercase())
+    }
+
+    /// Name and meaning, for tooltips.
+    pub fn describe(&self, idx: usize) -> String {
+        let Some(c) = self.entry(idx) else { return String::new() };
+        if c.meaning.is_empty() { c.name.clone() } else { format!("{} — {}", c.name, c.meaning) }
+    }
+
+    /// Accepts a color's name or meaning, a classic color name or a plain index.
+    pub fn parse(&self, s: &str) -> Option<usize> {
+        let s = s.trim();
+        let find = |colors: &[PaletteColor], meaning: bool| {
+            colors.iter().position(|c| c.name.eq_ignore_ascii_case(s) || (meaning && !c.meaning.is_empty() && c.meaning.eq_ignore_ascii_case(s)))
+        };
+        find(&self.colors, true)
+            .or_else(|| s.parse().ok().filter(|i| *i < self.colors.len()))
+            // Boards written before palettes used the classic names.
+            .or_else(|| find(&builtin()[0].colors, false).filter(|i| *i < self.colors.len()))
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        (0..self.colors.len()).map(|i| self.name(i)).collect()
+    }
+
+    /// The color closest to an arbitrary background, for notes from other apps.
+    pub fn nearest(&self, r: u8, g: u8, b: u8) -> usize {
+        let dist = |c: &PaletteColor| {
+            let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2);
+            d(r, c.rgb[0]) + d(g, c.rgb[1]) + d(b, c.rgb[2])
+        };
+        self.colors.iter().enumerate().min_by_key(|(_, c)| dist(c)).map_or(0, |(i, _)| i)
+    }
+}
+
+static CURRENT: RwLock<Option<Palette>> = RwLock::new(None);
+
+/// The palette in use, for code without one at hand (text formats, the
+/// command line). Loaded from the config file the first time.
+pub fn current() -> Palette {
+    if let Some(palette) = CURRENT.read().ok().and_then(|p| p.clone()) {
+        return palette;
+    }
+    let palette = Palette::load();
+    set_current(palette.clone());
+    palette
+}
+
+pub fn set_current(palette: Palette) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(palette);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_meanings_and_indexes() {
+        let mut palette = Palette::default();
+        palette.colors[1].meaning = "Meetings".to_string();
+        assert_eq!(palette.parse("Blue"), Some(1));
+        assert_eq!(palette.parse(" meetings "), Some(1));
+        assert_eq!(palette.parse("3"), Some(3));
+        assert_eq!(palette.parse("6"), None);
+        assert_eq!(palette.parse("teal"), None);
+        assert_eq!(palette.name(2), "pink");
+        assert_eq!(palette.describe(1), "Blue — Meetings");
+    }
+
+    #[test]
+    fn still_reads_classic_names_after_a_rename() {
+        let mut palette = Palette::default();
+        palette.colors[0].name = "Sun".to_string();
+        assert_eq!(palette.parse("sun"), Some(0));
+        assert_eq!(palette.parse("yellow"), Some(0));
+        palette.colors.truncate(2);
+        assert_eq!(palette.parse("peach"), None);
+    }
+
+    #[test]
+    fn finds_the_nearest_color() {
+        let palette = Palette::default();
+        assert_eq!(palette.nearest(255, 255, 153), 0);
+        assert_eq!(palette.nearest(180, 230, 250), 1);
+    }
+
+    #[test]
+    fn wraps_indexes_past_the_end() {
+        let palette = Palette::default();
+        assert_eq!(palette.color(7), palette.color(1));
+        assert_eq!(palette.name(7), "7");
+    }
+
+    #[test]
+    fn drops_empty_palettes_and_presets_from_the_file() {
+        let palette = Palette::from_json(r#"{ "colors": [], "presets": [
+            { "name": "Empty", "colors": [] },
+            { "name": "Mono", "colors": [ { "name": "Grey", "rgb": [128, 128, 128] } ] } ] }"#).unwrap();
+        assert_eq!(palette.colors.len(), Palette::default().colors.len());
+        assert_eq!(palette.presets.len(), 1);
+        assert_eq!(palette.presets[0].name, "Mono");
+    }
+
+    #[test]
+    fn an_empty_palette_does_not_panic() {
+        let palette = Palette { colors: Vec::new(), presets: Vec::new() };
+        assert_eq!(palette.color(3), egui::Color32::from_rgb(255, 235, 156));
+        assert_eq!(palette.describe(0), "");
+        assert_eq!(palette.nearest(1, 2, 3), 0);
+    }
+}
@@ -1,9 +1,17 @@
 use eframe::egui;
 use serde::{Deserialize, Serialize};
-use chrono::{Local, DateTime};
+use chrono::{Local, DateTime, Utc};
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Clone)]
+use crate::history::{Edit, HistoryAction};
+use crate::images;
+use crate::markdown_view;
+use crate::monitors;
+use crate::palette::Palette;
+use crate::settings::Settings;
+use crate::timefmt;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Pin {
     pub id: String,
     #[serde(default)]
@@ -13,7 +21,11 @@ pub struct Pin {
     #[serde(default)]
     pub is_completed: bool,
     #[serde(default)]
-    pub created_at: String,
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub modified_at: DateTime<Utc>,
     #[serde(default)]
     pub deadline: Option<DateTime<Local>>, 
     #[serde(default = "default_true")]
@@ -23,11 +35,117 @@ pub struct Pin {
     #[serde(default)]
     pub size: Option<(f32, f32)>,
     #[serde(default)]
+    pub position: Option<(f32, f32)>,
+    // Whether the open viewport has been placed at `position` yet.
+    #[serde(skip)]
+    pub placed: bool,
+    #[serde(default)]
     pub is_locked: bool,
     #[serde(skip)]
     pub show_menu: bool,
     #[serde(default = "default_true")]
     pub is_always_on_top: bool,
+    // For terminal output and code.
+    #[serde(default)]
+    pub monospace: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // The tag being typed in the options window.
+    #[serde(skip)]
+    pub new_tag: String,
+    // Show `content` rendered as Markdown while it is not being edited.
+    #[serde(default)]
+    pub markdown: bool,
+    // Set for checklist pins, whose body is these items instead of `content`.
+    #[serde(default)]
+    pub checklist: Option<Vec<ChecklistItem>>,
+    // Tick the pin off once every item is ticked.
+    #[serde(default)]
+    pub auto_complete: bool,
+    // The item being typed under a checklist.
+    #[serde(skip)]
+    pub new_item: String,
+    // File name of the pin's image in the images directory (see images.rs).
+    #[serde(default)]
+    pub image: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Hash)]
+pub struct ChecklistItem {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// Window geometry, which undo/redo leaves alone.
+#[derive(Clone, Copy)]
+pub struct Layout {
+    size: Option<(f32, f32)>,
+    position: Option<(f32, f32)>,
+    placed: bool,
+}
+
+/// What happened inside a pin viewport this frame.
+#[derive(Default)]
+pub struct PinResponse {
+    pub delete: bool,
+    pub clone: Option<Pin>,
+    pub history: Option<HistoryAction>,
+    /// What the user changed, for undo.
+    pub edit: Option<Edit>,
+    /// The window moved, resized or was hidden, which is saved but not undone.
+    pub moved: bool,
+    /// The pin got input this frame, which keeps an encrypted board unlocked.
+    pub active: bool,
+}
+
+/// Splits typed text into a title (the first line) and a body.
+pub fn split_text(text: &str) -> (String, String) {
+    let trimmed = text.trim();
+    match trimmed.split_once('\n') {
+        Some((first, rest)) => (first.trim().to_string(), rest.trim().to_string()),
+        None => (trimmed.to_string(), String::new()),
+    }
+}
+
+/// Reads `- [ ] item`, `[x] item` and plain lines as checklist items.
+pub fn parse_checklist(text: &str) -> Vec<ChecklistItem> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|line| {
+            let line = ["- ", "* ", "+ "].iter().find_map(|b| line.strip_prefix(b)).unwrap_or(line).trim_start();
+            match task_box(line) {
+                Some((done, text)) => ChecklistItem { text: text.to_string(), done },
+                None => ChecklistItem { text: line.to_string(), done: false },
+            }
+        })
+        .collect()
+}
+
+/// Whether every line of `text` is written as a `[ ]` task, so the pin
+/// reads better as a checklist.
+pub fn looks_like_checklist(text: &str) -> bool {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty()).peekable();
+    lines.peek().is_some()
+        && lines.all(|l| task_box(["- ", "* ", "+ "].iter().find_map(|b| l.strip_prefix(b)).unwrap_or(l)).is_some())
+}
+
+fn task_box(line: &str) -> Option<(bool, &str)> {
+    let (mark, rest) = (line.get(..3)?, &line[3..]);
+    let done = match mark {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    Some((done, rest.trim()))
+}
+
+/// Tidies a typed tag: no leading `#`, and spaces or commas become dashes.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches(['#', '+']);
+    let tag = tag.split(|c: char| c.is_whitespace() || c == ',').filter(|w| !w.is_empty()).collect::<Vec<_>>().join("-");
+    (!tag.is_empty()).then_some(tag)
 }
 
 fn default_true() -> bool { true }
@@ -35,34 +153,146 @@ fn default_opacity() -> f32 { 0.95 }
 
 impl Pin {
     pub fn new(title: String, content: String, deadline: Option<DateTime<Local>>) -> Self {
+        let now = Utc::now();
         Self {
             id: Uuid::new_v4().to_string(),
             title,
             content,
             color_idx: 0,
             is_completed: false,
-            created_at: Local::now().format("%I:%M %p").to_string(),
+            completed_at: None,
+            created_at: now,
+            modified_at: now,
             deadline,
             visible: true,
             opacity: 0.95,
             size: None,
+            position: None,
+            placed: false,
             is_locked: false,
             show_menu: false,
             is_always_on_top: true,
+            monospace: false,
+            tags: Vec::new(),
+            new_tag: String::new(),
+            markdown: false,
+            checklist: None,
+            auto_complete: false,
+            new_item: String::new(),
+            image: None,
+        }
+    }
+
+    /// The body as text: `content`, or one `- [ ] item` line per checklist item.
+    pub fn body_text(&self) -> String {
+        match &self.checklist {
+            Some(items) => items
+                .iter()
+                .map(|i| format!("- [{}] {}", if i.done { "x" } else { " " }, i.text))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => self.content.clone(),
+        }
+    }
+
+    /// Sets the body from text, as items if this is a checklist pin.
+    pub fn set_body_text(&mut self, text: String) {
+        match &mut self.checklist {
+            Some(items) => *items = parse_checklist(&text),
+            None => self.content = text,
+        }
+    }
+
+    pub fn make_checklist(&mut self) {
+        if self.checklist.is_some() { return; }
+        self.checklist = Some(parse_checklist(&self.content));
+        self.content.clear();
+    }
+
+    pub fn make_text(&mut self) {
+        if self.checklist.is_none() { return; }
+        self.content = self.body_text();
+        self.checklist = None;
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Whether the pin shows under the dashboard's tag filter.
+    pub fn matches_tag(&self, filter: Option<&str>) -> bool {
+        filter.is_none_or(|tag| self.has_tag(tag))
+    }
+
+    pub fn add_tag(&mut self, tag: &str) {
+        if let Some(tag) = normalize_tag(tag) {
+            if !self.has_tag(&tag) { self.tags.push(tag); }
+        }
+    }
+
+    /// Ticked and total items of a checklist pin.
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        self.checklist.as_ref().map(|items| (items.iter().filter(|i| i.done).count(), items.len()))
+    }
+
+    pub fn layout(&self) -> Layout {
+        Layout { size: self.size, position: self.position, placed: self.placed }
+    }
+
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.size = layout.size;
+        self.position = layout.position;
+        self.placed = layout.placed;
+    }
+
+    /// Shows the image `result` names, or logs why there is none.
+    pub fn attach_image(&mut self, result: Result<String, impl std::fmt::Display>) {
+        match result {
+            Ok(name) => self.image = Some(name),
+            Err(e) => eprintln!("Failed to add image: {e}"),
         }
     }
 
-    pub fn render(&mut self, ctx: &egui::Context) -> (bool, Option<Pin>) {
-        if !self.visible { return (false, None); }
+    pub fn set_completed(&mut self, done: bool) {
+        self.is_completed = done;
+        self.completed_at = if done { Some(Utc::now()) } else { None };
+    }
+
+    // Covers everything a user edits, so `modified_at` ignores moves and
+    // resizes. The typed text is hashed apart from the rest so that typing
+    // can undo as one step.
+    fn edit_hash(&self) -> (u64, u64) {
+        use std::hash::{Hash, Hasher};
+        let mut text = std::collections::hash_map::DefaultHasher::new();
+        (&self.title, &self.content).hash(&mut text);
+        let mut rest = std::collections::hash_map::DefaultHasher::new();
+        (self.color_idx, self.is_completed, self.is_locked, self.deadline).hash(&mut rest);
+        (self.opacity.to_bits(), self.is_always_on_top, self.monospace, &self.tags, self.markdown, &self.checklist, self.auto_complete, &self.image).hash(&mut rest);
+        (text.finish(), rest.finish())
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context, settings: &Settings, palette: &Palette) -> PinResponse {
+        if !self.visible {
+            self.placed = false;
+            return PinResponse::default();
+        }
+        let before = self.edit_hash();
+        let layout_before = (self.size, self.position, self.visible);
         
         let viewport_id = egui::ViewportId::from_hash_of(&self.id);
-        let builder = self.create_viewport_builder();
+        let builder = self.create_viewport_builder(ctx);
+        self.placed = true;
         
         // State to extract from closures
         let mut delete_requested = false;
         let mut clone_requested = None;
+        let mut history = None;
+        let mut active = false;
 
         ctx.show_viewport_immediate(viewport_id, builder, |ctx, _| {
+            history = HistoryAction::from_shortcuts(ctx);
+            active = ctx.input(|i| !i.events.is_empty());
+
             // Update size persistence
             if let Some(rect) = ctx.input(|i| i.viewport().inner_rect) {
                 let (w, h) = (rect.width(), rect.height());
@@ -77,9 +307,22 @@ impl Pin {
                 }
             }
 
-            let base_color = self.get_color();
+            // Update position persistence
+            if let Some(rect) = ctx.input(|i| i.viewport().outer_rect) {
+                let (x, y) = (rect.min.x, rect.min.y);
+                let moved = self.position.is_none_or(|(px, py)| (px - x).abs() > 1.0 || (py - y).abs() > 1.0);
+                if moved { self.position = Some((x, y)); }
+            }
+
+            let base_color = palette.color(self.color_idx);
             self.paint_background(ctx, base_color);
 
+            // An image file dropped onto the pin replaces its image.
+            if !self.is_locked {
+                let dropped = ctx.input(|i| i.raw.dropped_files.iter().filter_map(|f| f.path.clone()).find(|p| images::is_image(p)));
+                if let Some(path) = dropped { self.attach_image(images::import(&path)); }
+            }
+
             // 1. Header (Top)
             egui::TopBottomPanel::top("pin_header")
                 .frame(egui::Frame::none().inner_margin(egui::Margin { top: 12.0, left: 12.0, right: 12.0, bottom: 4.0 }))
@@ -93,7 +336,7 @@ impl Pin {
             egui::TopBottomPanel::bottom("pin_footer")
                 .frame(egui::Frame::none().inner_margin(egui::Margin { top: 4.0, left: 12.0, right: 12.0, bottom: 12.0 }))
                 .show(ctx, |ui| {
-                    self.render_footer(ui, ctx);
+                    self.render_footer(ui, ctx, settings, palette);
                 });
 
             // 3. Content (Fills Middle)
@@ -111,10 +354,19 @@ impl Pin {
             if ctx.input(|i| i.viewport().close_requested()) { delete_requested = true; }
         });
         
-        (delete_requested, clone_requested)
+        let after = self.edit_hash();
+        let edit = match (after.0 != before.0, after.1 != before.1) {
+            (_, true) => Some(Edit::Step),
+            (true, false) => Some(Edit::Text(self.id.clone())),
+            (false, false) => None,
+        };
+        if edit.is_some() { self.modified_at = Utc::now(); }
+        let moved = (self.size, self.position, self.visible) != layout_before;
+
+        PinResponse { delete: delete_requested, clone: clone_requested, history, edit, moved, active }
     }
 
-    fn create_viewport_builder(&self) -> egui::ViewportBuilder {
+    fn create_viewport_builder(&self, ctx: &egui::Context) -> egui::ViewportBuilder {
         let size = self.size.unwrap_or((280.0, 240.0));
         let mut builder = egui::ViewportBuilder::default()
             .with_title(if self.title.is_empty() { "Pin".to_string() } else { self.title.clone() })
@@ -127,22 +379,17 @@ impl Pin {
         if self.is_always_on_top {
             builder = builder.with_always_on_top();
         }
+
+        // Only when the window opens; afterwards the user moves it.
+        if let (false, Some(pos)) = (self.placed, self.position) {
+            let fallback = ctx.input(|i| i.viewport().monitor_size);
+            let pos = monitors::place(egui::pos2(pos.0, pos.1), egui::vec2(size.0, size.1), &monitors::areas(), fallback);
+            builder = builder.with_position(pos);
+        }
         
         builder
     }
 
-    fn get_color(&self) -> egui::Color32 {
-        let colors = [
-            egui::Color32::from_rgb(255, 235, 156), // Warm Yellow
-            egui::Color32::from_rgb(186, 237, 255), // Soft Blue
-            egui::Color32::from_rgb(255, 179, 186), // Pastel Pink
-            egui::Color32::from_rgb(179, 255, 196), // Mint Green
-            egui::Color32::from_rgb(222, 186, 255), // Lavender
-            egui::Color32::from_rgb(255, 218, 186), // Peach
-        ];
-        colors[self.color_idx % colors.len()]
-    }
-
     fn paint_background(&self, ctx: &egui::Context, color: egui::Color32) {
         let rect = ctx.available_rect();
         let painter = ctx.layer_painter(egui::LayerId::background());
@@ -161,7 +408,10 @@ impl Pin {
             ui.spacing_mut().item_spacing.x = 6.0;
 
             // Checkbox (Left)
-            ui.add_enabled(!self.is_locked, egui::Checkbox::new(&mut self.is_completed, ""));
+            let mut done = self.is_completed;
+            if ui.add_enabled(!self.is_locked, egui::Checkbox::new(&mut done, "")).changed() {
+                self.set_completed(done);
+            }
 
             // Drag Icon - small grip handle ONLY for dragging
             if !self.is_locked {
@@ -275,6 +525,62 @@ impl Pin {
                         self.show_menu = false;
                     }
                     
+                    if ui.button(if self.monospace { "Proportional Text" } else { "Monospace Text" }).clicked() {
+                        self.monospace = !self.monospace;
+                        self.show_menu = false;
+                    }
+
+                    if self.checklist.is_none() && ui.button(if self.markdown { "Show Raw Text" } else { "Render Markdown" }).clicked() {
+                        self.markdown = !self.markdown;
+                        self.show_menu = false;
+                    }
+
+                    if ui.add_enabled(!self.is_locked, egui::Button::new(if self.checklist.is_some() { "Convert to Text" } else { "Convert to Checklist" })).clicked() {
+                        if self.checklist.is_some() { self.make_text(); } else { self.make_checklist(); }
+                        self.show_menu = false;
+                    }
+
+                    if self.checklist.is_some() {
+                        ui.checkbox(&mut self.auto_complete, "Done when all ticked");
+                    }
+
+                    ui.separator();
+                    ui.label("Image");
+                    ui.add_enabled_ui(!self.is_locked, |ui| {
+                        ui.horizontal(|ui| {
+                            if ui.button(if self.image.is_some() { "Replace…" } else { "Choose…" }).clicked() {
+                                if let Some(path) = images::choose() {
+                                    self.attach_image(images::import(&path));
+                                }
+                            }
+                            if ui.button("Paste").clicked() {
+                                self.attach_image(images::paste());
+                            }
+                            if self.image.is_some() && ui.button("Remove").clicked() {
+                                self.image = None;
+                            }
+                        });
+                    });
+                    ui.label(egui::RichText::new("Or drop an image file onto the pin.").size(11.0).weak());
+
+                    ui.separator();
+                    ui.label("Tags");
+                    let mut remove = None;
+                    if !self.tags.is_empty() {
+                        ui.horizontal_wrapped(|ui| {
+                            for (i, tag) in self.tags.iter().enumerate() {
+                                if ui.small_button(format!("#{tag} ×")).on_hover_text("Remove tag").clicked() { remove = Some(i); }
+                            }
+                        });
+                    }
+                    if let Some(i) = remove { self.tags.remove(i); }
+                    let resp = ui.add(egui::TextEdit::singleline(&mut self.new_tag).hint_text("Add a tag…").desired_width(160.0));
+                    if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        let tag = std::mem::take(&mut self.new_tag);
+                        self.add_tag(&tag);
+                        resp.request_focus();
+                    }
+
                     ui.separator();
                     ui.label("Transparency");
                     ui.add(egui::Slider::new(&mut self.opacity, 0.2..=1.0).show_value(false));
@@ -315,6 +621,7 @@ impl Pin {
             egui::Color32::from_rgb(30, 30, 30) 
         };
 
+        let area = ui.available_size();
         egui::ScrollArea::vertical()
             .auto_shrink([false; 2]) // Allow growing
             .max_height(f32::INFINITY)
@@ -332,12 +639,61 @@ impl Pin {
                      ui.add_space(4.0);
                  }
 
-                // Content
+                if let Some(name) = &self.image {
+                    // Fit the pin, leaving a line for the text below.
+                    let fit = egui::vec2(ui.available_width(), (area.y - ui.min_rect().height() - 24.0).max(40.0));
+                    match images::texture(ui.ctx(), name) {
+                        Some(texture) => {
+                            ui.add(egui::Image::new(&texture).max_size(fit).maintain_aspect_ratio(true).rounding(4.0));
+                        }
+                        None => {
+                            ui.label(egui::RichText::new("Image missing").size(13.0).color(egui::Color32::from_black_alpha(80)));
+                        }
+                    }
+                    ui.add_space(4.0);
+                }
+
+                if self.checklist.is_some() {
+                    self.render_checklist(ui, text_color);
+                    return;
+                }
+
+                // Content: rendered, unless the editor has keyboard focus in a
+                // focused pin. Locked pins cannot be edited, so they stay rendered.
+                let edit_id = egui::Id::new(("pin_content", &self.id));
+                let window_focused = ui.input(|i| i.viewport().focused.unwrap_or(true));
+                // Focusing the pin opens the editor, as a double-click does. Not
+                // when it first appears, as a new window may start out focused.
+                let focus_id = edit_id.with("window_focused");
+                let was_focused = ui.data(|d| d.get_temp::<bool>(focus_id));
+                ui.data_mut(|d| d.insert_temp(focus_id, window_focused));
+                if self.markdown && !self.is_locked && window_focused && was_focused == Some(false) {
+                    ui.memory_mut(|m| m.request_focus(edit_id));
+                }
+                let editing = window_focused && ui.memory(|m| m.has_focus(edit_id));
+                if self.markdown && (self.is_locked || !editing) {
+                    let resp = markdown_view::show(ui, &self.content, text_color, 16.0);
+                    let area = resp.rect.union(ui.available_rect_before_wrap());
+                    if self.content.is_empty() && !self.is_locked {
+                        ui.label(egui::RichText::new("Double-click to write").size(13.0).color(egui::Color32::from_black_alpha(80)));
+                    }
+                    let double_clicked = ui.input(|i| {
+                        i.pointer.button_double_clicked(egui::PointerButton::Primary)
+                            && i.pointer.interact_pos().is_some_and(|p| area.contains(p))
+                    });
+                    if double_clicked && !self.is_locked {
+                        ui.memory_mut(|m| m.request_focus(edit_id));
+                        ui.ctx().request_repaint();
+                    }
+                    return;
+                }
+
                 let mut edit = egui::TextEdit::multiline(&mut self.content)
+                    .id(edit_id)
                     .frame(false)
                     .desired_width(f32::INFINITY)
                     .text_color(text_color)
-                    .font(egui::FontId::proportional(16.0))
+                    .font(if self.monospace { egui::FontId::monospace(13.0) } else { egui::FontId::proportional(16.0) })
                     .margin(egui::vec2(0.0, 4.0));
                 
                 if self.is_locked { edit = edit.interactive(false); }
@@ -354,24 +710,86 @@ impl Pin {
             });
     }
 
-    fn render_footer(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+    fn render_checklist(&mut self, ui: &mut egui::Ui, text_color: egui::Color32) {
+        let locked = self.is_locked;
+        let font = if self.monospace { egui::FontId::monospace(13.0) } else { egui::FontId::proportional(15.0) };
+        let Some(items) = &mut self.checklist else { return };
+        let (mut ticked, mut remove, mut swap) = (false, None, None);
+        let count = items.len();
+
+        for (i, item) in items.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 4.0;
+                if ui.add_enabled(!locked, egui::Checkbox::without_text(&mut item.done)).changed() { ticked = true; }
+
+                let buttons = if locked { 0.0 } else { 60.0 };
+                let color = if item.done { egui::Color32::from_black_alpha(100) } else { text_color };
+                let edit = egui::TextEdit::singleline(&mut item.text)
+                    .frame(false)
+                    .interactive(!locked)
+                    .text_color(color)
+                    .font(font.clone())
+                    .desired_width((ui.available_width() - buttons).max(40.0));
+                let resp = ui.add(edit);
+                if item.done {
+                    let rect = resp.rect;
+                    let width = ui.fonts(|f| f.layout_no_wrap(item.text.clone(), font.clone(), color).size().x).min(rect.width());
+                    ui.painter().line_segment(
+                        [egui::pos2(rect.left(), rect.center().y), egui::pos2(rect.left() + width, rect.center().y)],
+                        egui::Stroke::new(1.2, egui::Color32::BLACK.gamma_multiply(0.4)),
+                    );
+                }
+
+                if !locked {
+                    let small = |ui: &mut egui::Ui, enabled: bool, label: &str| {
+                        ui.add_enabled(enabled, egui::Button::new(egui::RichText::new(label).size(10.0)).frame(false)).clicked()
+                    };
+                    if small(ui, i > 0, "▲") { swap = Some((i - 1, i)); }
+                    if small(ui, i + 1 < count, "▼") { swap = Some((i, i + 1)); }
+                    if small(ui, true, "×") { remove = Some(i); }
+                }
+            });
+        }
+
+        if let Some((a, b)) = swap { items.swap(a, b); }
+        if let Some(i) = remove { items.remove(i); }
+
+        if !locked {
+            let resp = ui.add(
+                egui::TextEdit::singleline(&mut self.new_item)
+                    .frame(false)
+                    .hint_text("+ Add item")
+                    .text_color(text_color)
+                    .font(font)
+                    .desired_width(f32::INFINITY),
+            );
+            if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) && !self.new_item.trim().is_empty() {
+                let text = std::mem::take(&mut self.new_item).trim().to_string();
+                items.push(ChecklistItem { text, done: false });
+                resp.request_focus();
+            }
+        }
+
+        if ticked && self.auto_complete {
+            let all = items.iter().all(|i| i.done);
+            if all != self.is_completed { self.set_completed(all); }
+        }
+    }
+
+    fn render_footer(&mut self, ui: &mut egui::Ui, ctx: &egui::Context, settings: &Settings, palette: &Palette) {
         ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
             ui.add_space(4.0);
             
             ui.horizontal(|ui| {
                 // Color Switcher
-                let colors = [
-                    egui::Color32::from_rgb(255, 235, 156), egui::Color32::from_rgb(186, 237, 255),
-                    egui::Color32::from_rgb(255, 179, 186), egui::Color32::from_rgb(179, 255, 196),
-                    egui::Color32::from_rgb(222, 186, 255), egui::Color32::from_rgb(255, 218, 186),
-                ];
-
                 ui.spacing_mut().item_spacing.x = 6.0;
-                for (i, &c) in colors.iter().enumerate() {
+                for i in 0..palette.colors.len() {
                     let (rect, resp) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::click());
+                    let resp = resp.on_hover_text(palette.describe(i));
                     let painter = ui.painter();
-                    painter.circle_filled(rect.center(), 5.0, c);
-                    if i == self.color_idx {
+                    painter.circle_filled(rect.center(), 5.0, palette.color(i));
+                    painter.circle_stroke(rect.center(), 5.0, egui::Stroke::new(0.5, egui::Color32::BLACK.gamma_multiply(0.25)));
+                    if i == self.color_idx % palette.colors.len() {
                         painter.circle_stroke(rect.center(), 6.5, egui::Stroke::new(1.5, egui::Color32::BLACK.gamma_multiply(0.5)));
                     }
                     if resp.clicked() { self.color_idx = i; }
@@ -408,7 +826,14 @@ impl Pin {
                     }
 
                     ui.add_space(8.0);
-                    ui.label(egui::RichText::new(&self.created_at).size(8.0).color(egui::Color32::BLACK.gamma_multiply(0.4)));
+                    let now = Utc::now();
+                    let h24 = settings.clock_24h;
+                    let mut details = format!("Created {}\nEdited {}", timefmt::absolute(self.created_at, h24), timefmt::absolute(self.modified_at, h24));
+                    if let Some(done) = self.completed_at {
+                        details.push_str(&format!("\nDone {}", timefmt::absolute(done, h24)));
+                    }
+                    ui.label(egui::RichText::new(timefmt::relative(self.created_at, now)).size(8.0).color(egui::Color32::BLACK.gamma_multiply(0.4)))
+                        .on_hover_text(details);
                     
                     // Completion Status
                     if self.is_completed {
@@ -417,11 +842,31 @@ impl Pin {
                         ui.label(egui::RichText::new("○ Pending").size(9.0).color(egui::Color32::BLACK.gamma_multiply(0.5)));
                     }
                     
+                    if let Some((done, total)) = self.progress() {
+                        ui.label(egui::RichText::new(format!("{done}/{total}")).size(9.0).strong().color(egui::Color32::BLACK.gamma_multiply(0.5)));
+                    }
+
                     if self.is_locked {
                         ui.label(egui::RichText::new("🔒").size(9.0));
                     }
                 });
             });
+
+            // Tags, above the colors
+            if !self.tags.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 4.0;
+                    for tag in &self.tags {
+                        egui::Frame::none()
+                            .fill(egui::Color32::from_black_alpha(20))
+                            .rounding(6.0)
+                            .inner_margin(egui::Margin::symmetric(5.0, 1.0))
+                            .show(ui, |ui| {
+                                ui.label(egui::RichText::new(format!("#{tag}")).size(9.0).color(egui::Color32::BLACK.gamma_multiply(0.6)));
+                            });
+                    }
+                });
+            }
         });
     }
 }
@@ -0,0 +1,255 @@
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+use crate::migrate;
+use crate::pin::Pin;
+use crate::settings::Settings;
+use crate::trash::TrashedPin;
+
+/// What happened to an unreadable board, shown as a banner on the dashboard.
+pub struct RecoveryReport {
+    pub error: String,
+    pub quarantined: Option<PathBuf>,
+    pub recovered: Vec<String>,
+    pub lost: Vec<String>,
+}
+
+pub struct Salvage {
+    pub pins: Vec<Pin>,
+    pub archive: Vec<Pin>,
+    pub trash: Vec<TrashedPin>,
+    /// `None` when the settings were cut off or unreadable.
+    pub settings: Option<Settings>,
+    pub recovered: Vec<String>,
+    pub lost: Vec<String>,
+}
+
+/// Rescues every pin that still parses on its own from a damaged document,
+/// on the board, in the archive and in the trash.
+///
+/// Works on text rather than a parsed `Value` because the usual damage is a
+/// file cut off mid-write, which is not valid JSON as a whole.
+pub fn salvage(raw: &str) -> Salvage {
+    let version = schema_version(raw);
+    let mut out = Salvage {
+        pins: Vec::new(),
+        archive: Vec::new(),
+        trash: Vec::new(),
+        settings: None,
+        recovered: Vec::new(),
+        lost: Vec::new(),
+    };
+
+    for (key, place) in [("pins", ""), ("archive", " (archive)"), ("trash", " (trash)")] {
+        let Some(body) = top_level_value(raw, key) else { continue };
+        for (n, chunk) in object_chunks(body).into_iter().enumerate() {
+            let value = serde_json::from_str::<Value>(chunk).ok();
+            let migrated = value.clone().and_then(|v| migrate_entry(key, v, version));
+            let recovered = match (key, migrated) {
+                ("trash", Some(v)) => serde_json::from_value::<TrashedPin>(v).ok().map(|t| {
+                    let title = label(&t.pin);
+                    out.trash.push(t);
+                    title
+                }),
+                (_, Some(v)) => serde_json::from_value::<Pin>(v).ok().map(|pin| {
+                    let title = label(&pin);
+                    if key == "pins" { out.pins.push(pin) } else { out.archive.push(pin) }
+                    title
+                }),
+                _ => None,
+            };
+            if let Some(title) = recovered {
+                out.recovered.push(title + place);
+            } else {
+                let pin = value.as_ref().map(|v| if key == "trash" { &v["pin"] } else { v });
+                let hint = pin
+                    .and_then(|v| v.get("title").filter(|t| t.as_str().is_some_and(|t| !t.trim().is_empty())).or_else(|| v.get("content")))
+                    .and_then(Value::as_str)
+                    .filter(|s| !s.trim().is_empty())
+                    .map(preview)
+                    .unwrap_or_else(|| format!("Pin #{}", n + 1));
+                out.lost.push(hint + place);
+            }
+        }
+    }
+
+    if let Some(body) = top_level_value(raw, "settings") {
+        out.settings = object_chunks(body).first().and_then(|c| serde_json::from_str(c).ok());
+        if out.settings.is_none() { out.lost.push("Settings".to_string()); }
+    }
+    out
+}
+
+// Runs one `key` entry (a pin, or a trashed pin) through the migrations.
+fn migrate_entry(key: &str, entry: Value, version: u64) -> Option<Value> {
+    let mut doc = json!({ "schema_version": version, key: [entry] });
+    migrate::migrate(&mut doc).ok()?;
+    Some(doc[key][0].take())
+}
+
+fn label(pin: &Pin) -> String {
+    let text = if pin.title.trim().is_empty() { &pin.content } else { &pin.title };
+    if text.trim().is_empty() { format!("Untitled ({})", &pin.id) } else { preview(text) }
+}
+
+fn preview(text: &str) -> String {
+    let line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    if line.chars().count() > 40 {
+        format!("{}…", line.chars().take(40).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+fn schema_version(raw: &str) -> u64 {
+    let Some(at) = raw.find("\"schema_version\"") else { return 0 };
+    raw[at + "\"schema_version\"".len()..]
+        .trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .unwrap_or(0)
+}
+
+// The text after `"key":` in the outermost object, up to the end of the
+// document. Keys inside pins and strings do not count.
+fn top_level_value<'a>(raw: &'a str, key: &str) -> Option<&'a str> {
+    let mut depth = 0usize;
+    let mut string_start = None;
+    let mut escaped = false;
+
+    for (i, c) in raw.char_indices() {
+        if let Some(start) = string_start {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => {
+                    string_start = None;
+                    if depth == 1 && raw[start + 1..i] == *key {
+                        let rest = raw[i + 1..].trim_start();
+                        if let Some(value) = rest.strip_prefix(':') { return Some(value.trim_start()); }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => string_start = Some(i),
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    None
+}
+
+// Splits a JSON value into the text of each outermost object: the items of
+// an array, or the value itself if it is an object. An object that was cut
+// off is included as it is.
+fn object_chunks(body: &str) -> Vec<&str> {
+    let (body, in_array) = match body.strip_prefix('[') {
+        Some(rest) => (rest, true),
+        None => (body, false),
+    };
+
+    let mut chunks = Vec::new();
+    let mut depth = 0usize;
+    let mut start = None;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, c) in body.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => {
+                if depth == 0 { start = Some(i); }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    if let Some(s) = start.take() { chunks.push(&body[s..=i]); }
+                    if !in_array { return chunks; }
+                }
+            }
+            ']' if depth == 0 => return chunks,
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        chunks.push(&body[s..]);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: &str = r#"{
+  "schema_version": 2,
+  "pins": [
+    { "id": "a", "title": "Deploy", "content": "at 5", "color_idx": 1 },
+    { "id": "b", "title": "", "content": "Buy \"pins\" and milk", "color_idx": 0 }
+  ],
+  "archive": [
+    { "id": "c", "title": "Old", "content": "done", "color_idx": 2 }
+  ],
+  "trash": [
+    { "pin": { "id": "d", "title": "Gone", "content": "", "color_idx": 3 }, "deleted_at": "2026-10-01T12:00:00Z" },
+    { "pin": { "id": "e", "title": "Half", "content": "cut off here"#;
+
+    #[test]
+    fn salvages_every_section_of_a_truncated_file() {
+        let salvage = salvage(BOARD);
+        let ids = |pins: &[Pin]| pins.iter().map(|p| p.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&salvage.pins), ["a", "b"]);
+        assert_eq!(salvage.pins[1].content, "Buy \"pins\" and milk");
+        assert_eq!(ids(&salvage.archive), ["c"]);
+        assert_eq!(salvage.trash.len(), 1);
+        assert_eq!(salvage.trash[0].pin.id, "d");
+        assert_eq!(salvage.recovered, ["Deploy", "Buy \"pins\" and milk", "Old (archive)", "Gone (trash)"]);
+        assert_eq!(salvage.lost, ["Pin #2 (trash)"]);
+        // Cut off before the settings were written.
+        assert!(salvage.settings.is_none());
+    }
+
+    #[test]
+    fn counts_unreadable_pins_and_settings_as_lost() {
+        let raw = r#"{ "pins": [ { "id": "a", "title": "Fine", "content": "", "color_idx": 0 },
+            { "id": 7, "title": "Broken id", "content": "", "color_idx": 0 } ],
+            "archive": [ { "title": "No id", "content": "x", "color_idx": "blue" } ],
+            "settings": { "trash_retention_days": "#;
+        let salvage = salvage(raw);
+        assert_eq!(salvage.recovered, ["Fine"]);
+        assert_eq!(salvage.lost, ["Broken id", "No id (archive)", "Settings"]);
+    }
+
+    #[test]
+    fn keeps_settings_that_were_written_in_full() {
+        let raw = r#"{ "pins": [], "settings": { "trash_retention_days": 7 }, "trash": [ { "pin": "#;
+        let salvage = salvage(raw);
+        assert_eq!(salvage.settings.map(|s| s.trash_retention_days), Some(7));
+        assert_eq!(salvage.lost, ["Pin #1 (trash)"]);
+    }
+
+    #[test]
+    fn migrates_pins_from_old_releases() {
+        let raw = r#"{ "pins": [ { "id": "a", "content": "old", "color_idx": 0, "created_at": "09:30 AM", "opacity": 0.0 }"#;
+        let salvage = salvage(raw);
+        assert_eq!(salvage.pins.len(), 1);
+        assert_eq!(salvage.pins[0].opacity, 0.95);
+        assert!(salvage.pins[0].is_always_on_top);
+    }
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::trash;
+
+/// User preferences, saved with the board.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub trash_retention_days: u32,
+    pub clock_24h: bool,
+    /// A todo.txt file kept in step with the board while the app runs.
+    pub todo_txt_mirror: Option<PathBuf>,
+    /// A folder holding one Markdown file per pin.
+    pub markdown_folder: Option<PathBuf>,
+    /// Minutes without input before an encrypted board locks; 0 never locks.
+    pub auto_lock_minutes: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            trash_retention_days: trash::DEFAULT_RETENTION_DAYS,
+            clock_24h: false,
+            todo_txt_mirror: None,
+            markdown_folder: None,
+            auto_lock_minutes: 10,
+        }
+    }
+}
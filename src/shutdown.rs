@@ -0,0 +1,44 @@
+use eframe::egui;
+#[cfg(unix)]
+use signal_hook::{consts::{SIGINT, SIGTERM}, iterator::Signals};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A request to save and quit, raised from the tray or a signal and acted on
+/// by the egui loop.
+#[derive(Clone, Default)]
+pub struct Shutdown(Arc<AtomicBool>);
+
+impl Shutdown {
+    pub fn request(&self, ctx: &egui::Context) {
+        self.0.store(true, Ordering::SeqCst);
+        ctx.request_repaint();
+    }
+
+    pub fn requested(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Turns SIGTERM and SIGINT into shutdown requests. A second signal exits
+    /// right away, in case the loop is stuck.
+    #[cfg(unix)]
+    pub fn listen_for_signals(&self, ctx: egui::Context) -> std::io::Result<()> {
+        let mut signals = Signals::new([SIGTERM, SIGINT])?;
+        let shutdown = self.clone();
+        std::thread::spawn(move || {
+            for signal in signals.forever() {
+                if shutdown.requested() {
+                    std::process::exit(128 + signal);
+                }
+                shutdown.request(&ctx);
+            }
+        });
+        Ok(())
+    }
+
+    /// Windows has no SIGTERM or SIGINT to listen for.
+    #[cfg(not(unix))]
+    pub fn listen_for_signals(&self, _ctx: egui::Context) -> std::io::Result<()> {
+        Ok(())
+    }
+}
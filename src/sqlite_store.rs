@@ -0,0 +1,266 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::pin::Pin;
+use crate::store::{self, quarantine_path, PinStore};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS pins (
+    id           TEXT PRIMARY KEY,
+    sort_order   INTEGER NOT NULL,
+    title        TEXT NOT NULL,
+    content      TEXT NOT NULL,
+    color_idx    INTEGER NOT NULL,
+    is_completed INTEGER NOT NULL,
+    data         TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS board (
+    key   TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+";
+
+/// One row per pin, so a save only touches the pins that changed.
+///
+/// `data` holds the pin exactly as in `pins.json`; the other columns are
+/// copies for querying the database from outside the app. Everything that is
+/// not a pin is kept as one JSON object under `board.key = 'state'`.
+pub struct SqliteStore {
+    path: PathBuf,
+    conn: Connection,
+    // What the database currently holds, to skip unchanged rows on save.
+    written: HashMap<String, (usize, String)>,
+    written_state: Option<String>,
+}
+
+fn sql_err(e: rusqlite::Error) -> io::Error {
+    io::Error::other(e)
+}
+
+impl SqliteStore {
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(&path).map_err(sql_err)?;
+        conn.execute_batch(SCHEMA).map_err(sql_err)?;
+
+        let written = {
+            let mut stmt = conn.prepare("SELECT id, sort_order, data FROM pins").map_err(sql_err)?;
+            let rows = stmt
+                .query_map([], |r| Ok((r.get::<_, String>(0)?, (r.get::<_, i64>(1)? as usize, r.get::<_, String>(2)?))))
+                .map_err(sql_err)?;
+            rows.collect::<Result<HashMap<_, _>, _>>().map_err(sql_err)?
+        };
+        let written_state = conn
+            .query_row("SELECT value FROM board WHERE key = 'state'", [], |r| r.get(0))
+            .optional()
+            .map_err(sql_err)?;
+        Ok(Self { path, conn, written, written_state })
+    }
+
+    fn write_pin(conn: &Connection, order: usize, data: &str) -> io::Result<()> {
+        let pin: Value = serde_json::from_str(data).map_err(io::Error::other)?;
+        let text = |key: &str| pin.get(key).and_then(Value::as_str).unwrap_or("").to_string();
+        conn.execute(
+            "INSERT INTO pins (id, sort_order, title, content, color_idx, is_completed, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(id) DO UPDATE SET
+                sort_order = excluded.sort_order, title = excluded.title, content = excluded.content,
+                color_idx = excluded.color_idx, is_completed = excluded.is_completed, data = excluded.data",
+            params![
+                text("id"),
+                order as i64,
+                text("title"),
+                text("content"),
+                pin.get("color_idx").and_then(Value::as_i64).unwrap_or(0),
+                pin.get("is_completed").and_then(Value::as_bool).unwrap_or(false),
+                data,
+            ],
+        )
+        .map_err(sql_err)?;
+        Ok(())
+    }
+}
+
+impl PinStore for SqliteStore {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&self) -> io::Result<Option<String>> {
+        let state: Option<String> = self
+            .conn
+            .query_row("SELECT value FROM board WHERE key = 'state'", [], |r| r.get(0))
+            .optional()
+            .map_err(sql_err)?;
+        let mut stmt = self.conn.prepare("SELECT data FROM pins ORDER BY sort_order").map_err(sql_err)?;
+        let rows = stmt
+            .query_map([], |r| r.get::<_, String>(0))
+            .map_err(sql_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(sql_err)?;
+
+        if state.is_none() && rows.is_empty() {
+            return Ok(None);
+        }
+
+        // Stitched together as text rather than through `Value` so a single bad
+        // row still reaches recovery with the good rows around it.
+        let state = state.unwrap_or_else(|| "{}".to_string());
+        let rest = state.trim().strip_prefix('{').unwrap_or("}").trim_start();
+        let sep = if rest.starts_with('}') { "" } else { "," };
+        Ok(Some(format!("{{\"pins\":[{}]{sep}{rest}", rows.join(","))))
+    }
+
+    fn save(&mut self, doc: &Value) -> io::Result<()> {
+        let mut state = doc.clone();
+        let pins = match state.as_object_mut().and_then(|o| o.remove("pins")) {
+            Some(Value::Array(pins)) => pins,
+            _ => Vec::new(),
+        };
+
+        let mut current = HashMap::with_capacity(pins.len());
+        for (order, pin) in pins.iter().enumerate() {
+            let id = pin.get("id").and_then(Value::as_str).unwrap_or_default().to_string();
+            current.insert(id, (order, pin.to_string()));
+        }
+        let state = state.to_string();
+
+        let tx = self.conn.transaction().map_err(sql_err)?;
+        for (id, row) in &current {
+            if self.written.get(id) != Some(row) {
+                Self::write_pin(&tx, row.0, &row.1)?;
+            }
+        }
+        for id in self.written.keys().filter(|id| !current.contains_key(*id)) {
+            tx.execute("DELETE FROM pins WHERE id = ?1", [id]).map_err(sql_err)?;
+        }
+        if self.written_state.as_ref() != Some(&state) {
+            tx.execute(
+                "INSERT INTO board (key, value) VALUES ('state', ?1)
+                 ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                [&state],
+            )
+            .map_err(sql_err)?;
+        }
+        tx.commit().map_err(sql_err)?;
+
+        self.written = current;
+        self.written_state = Some(state);
+        Ok(())
+    }
+
+    fn upsert(&mut self, pin: &Pin) -> io::Result<()> {
+        let data = serde_json::to_value(pin).map_err(io::Error::other)?.to_string();
+        let order = match self.written.get(&pin.id) {
+            Some((order, _)) => *order,
+            None => self
+                .conn
+                .query_row("SELECT COALESCE(MAX(sort_order) + 1, 0) FROM pins", [], |r| r.get::<_, i64>(0))
+                .map_err(sql_err)? as usize,
+        };
+        Self::write_pin(&self.conn, order, &data)?;
+        self.written.insert(pin.id.clone(), (order, data));
+        Ok(())
+    }
+
+    fn delete(&mut self, id: &str) -> io::Result<()> {
+        self.conn.execute("DELETE FROM pins WHERE id = ?1", [id]).map_err(sql_err)?;
+        self.written.remove(id);
+        Ok(())
+    }
+
+    // Deleted rows linger in free pages and the WAL until they are rewritten.
+    fn scrub(&mut self) -> io::Result<()> {
+        self.conn.execute_batch("VACUUM; PRAGMA wal_checkpoint(TRUNCATE);").map_err(sql_err)?;
+        store::scrub_eframe_board()
+    }
+
+    fn quarantine(&mut self, _raw: &str) -> io::Result<PathBuf> {
+        let target = quarantine_path(&self.path, "db");
+        self.conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);").map_err(sql_err)?;
+        fs::copy(&self.path, &target)?;
+        self.conn.execute_batch("DELETE FROM pins; DELETE FROM board;").map_err(sql_err)?;
+        self.written.clear();
+        self.written_state = None;
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_db(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("pin-board-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("pins.db")
+    }
+
+    fn titles(store: &SqliteStore) -> Vec<String> {
+        let doc: Value = serde_json::from_str(&store.load().unwrap().unwrap()).unwrap();
+        doc["pins"].as_array().unwrap().iter().map(|p| p["title"].as_str().unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn round_trips_saves_and_single_pin_writes() {
+        let path = temp_db("sqlite-round-trip");
+        let mut store = SqliteStore::open(path.clone()).unwrap();
+        assert!(store.load().unwrap().is_none());
+
+        let mut pins: Vec<Pin> = ["a", "b", "c"].iter().map(|t| Pin::new(t.to_string(), String::new(), None)).collect();
+        let doc = json!({ "schema_version": 2, "pins": pins, "settings": { "clock_24h": true } });
+        store.save(&doc).unwrap();
+        let loaded: Value = serde_json::from_str(&store.load().unwrap().unwrap()).unwrap();
+        assert_eq!(loaded, doc);
+
+        // Changed and removed pins are written; the board state is kept.
+        pins[1].title = "B".to_string();
+        let c = pins.pop().unwrap();
+        store.save(&json!({ "schema_version": 2, "pins": pins, "settings": { "clock_24h": true } })).unwrap();
+        assert_eq!(titles(&store), ["a", "B"]);
+
+        let mut d = Pin::new("d".to_string(), String::new(), None);
+        store.upsert(&d).unwrap();
+        d.title = "D".to_string();
+        store.upsert(&d).unwrap();
+        store.upsert(&c).unwrap();
+        assert_eq!(titles(&store), ["a", "B", "D", "c"]);
+
+        store.delete(&pins[0].id).unwrap();
+        store.delete("no such pin").unwrap();
+        assert_eq!(titles(&store), ["B", "D", "c"]);
+
+        // A second connection sees the same board and settings.
+        let reopened = SqliteStore::open(path.clone()).unwrap();
+        assert_eq!(titles(&reopened), ["B", "D", "c"]);
+        let doc: Value = serde_json::from_str(&reopened.load().unwrap().unwrap()).unwrap();
+        assert_eq!(doc["settings"]["clock_24h"], true);
+        assert_eq!(reopened.written.len(), 3);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn a_damaged_row_still_reaches_recovery() {
+        let path = temp_db("sqlite-damaged");
+        let mut store = SqliteStore::open(path.clone()).unwrap();
+        let pins = vec![Pin::new("kept".to_string(), String::new(), None), Pin::new("lost".to_string(), String::new(), None)];
+        store.save(&json!({ "schema_version": 2, "pins": pins })).unwrap();
+        store.conn.execute("UPDATE pins SET data = '{\"id\": \"x\", \"tit' WHERE title = 'lost'", []).unwrap();
+
+        let text = store.load().unwrap().unwrap();
+        assert!(serde_json::from_str::<Value>(&text).is_err());
+        let salvage = crate::recovery::salvage(&text);
+        assert_eq!(salvage.pins.len(), 1);
+        assert_eq!(salvage.pins[0].title, "kept");
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,351 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::pin::Pin;
+use crate::sqlite_store::SqliteStore;
+
+const FILE_NAME: &str = "pins.json";
+const BACKUP_COUNT: usize = 5;
+const BACKUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Where the board lives between runs.
+///
+/// Boards travel as the JSON document described in docs/STORAGE.md, so
+/// migrations and recovery work the same for every backend.
+pub trait PinStore {
+    fn path(&self) -> &Path;
+
+    /// Returns `None` when nothing has been saved yet.
+    fn load(&self) -> io::Result<Option<String>>;
+
+    /// Replaces the stored board with `doc`.
+    fn save(&mut self, doc: &Value) -> io::Result<()>;
+
+    // Single-pin writes for tools that edit the store without a running board.
+    fn upsert(&mut self, pin: &Pin) -> io::Result<()>;
+
+    fn delete(&mut self, id: &str) -> io::Result<()>;
+
+    /// Sets an unreadable board aside as `pins.corrupt-<timestamp>.*` and
+    /// returns where it went. `raw` is the payload that failed to load.
+    fn quarantine(&mut self, raw: &str) -> io::Result<PathBuf>;
+
+    /// Drops older copies of the board kept beside the current one, and the
+    /// one in eframe's `app.ron`, so that after encryption is turned on no
+    /// plaintext copy is left behind.
+    fn scrub(&mut self) -> io::Result<()>;
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Backend {
+    Json,
+    Sqlite,
+}
+
+impl Backend {
+    /// Chosen with `PIN_BOARD_STORE=json|sqlite`, JSON by default.
+    pub fn from_env() -> Self {
+        match std::env::var("PIN_BOARD_STORE").as_deref() {
+            Ok("sqlite") => Backend::Sqlite,
+            _ => Backend::Json,
+        }
+    }
+}
+
+pub fn open_default() -> Option<Box<dyn PinStore>> {
+    let dir = data_dir()?;
+    match Backend::from_env() {
+        Backend::Json => Some(Box::new(JsonStore::new(dir.join(FILE_NAME)))),
+        Backend::Sqlite => match SqliteStore::open(dir.join("pins.db")) {
+            Ok(store) => Some(Box::new(store)),
+            Err(e) => {
+                eprintln!("Failed to open SQLite store: {e}");
+                None
+            }
+        },
+    }
+}
+
+/// The name eframe keeps its own `app.ron` under.
+pub const APP_ID: &str = "Pin-Board";
+
+/// The board from wherever it was kept before, for the first run with a
+/// backend: the JSON backend's file after switching away from it, or
+/// eframe's `app.ron`, where 0.2 and earlier saved it.
+pub fn previous_board() -> Option<String> {
+    let json = match Backend::from_env() {
+        Backend::Json => None,
+        _ => JsonStore::new(data_dir()?.join(FILE_NAME)).load().ok().flatten(),
+    };
+    json.or_else(eframe_board)
+}
+
+fn eframe_board() -> Option<String> {
+    let kv = eframe_storage(&eframe::storage_dir(APP_ID)?.join("app.ron")).ok()?;
+    kv.get(LEGACY_KEY).filter(|s| !s.is_empty()).cloned()
+}
+
+fn eframe_storage(path: &Path) -> io::Result<HashMap<String, String>> {
+    ron::from_str(&fs::read_to_string(path)?).map_err(io::Error::other)
+}
+
+/// Drops the plain-text board 0.2 and earlier left in eframe's `app.ron`.
+/// The running app also blanks it in eframe's own copy (see `AppState::save`),
+/// which would otherwise write it back.
+pub fn scrub_eframe_board() -> io::Result<()> {
+    let Some(path) = eframe::storage_dir(APP_ID).map(|d| d.join("app.ron")) else { return Ok(()) };
+    let mut kv = match eframe_storage(&path) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        kv => kv?,
+    };
+    if kv.remove(LEGACY_KEY).is_some() {
+        let text = ron::ser::to_string_pretty(&kv, Default::default()).map_err(io::Error::other)?;
+        write_atomic(&path, text.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// The eframe storage key the board was saved under before it had a store.
+pub const LEGACY_KEY: &str = "pinboard_state";
+
+/// `$XDG_DATA_HOME/pin-board`, falling back to `~/.local/share/pin-board`.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| home_dir().map(|h| h.join(".local").join("share")))?;
+    Some(base.join("pin-board"))
+}
+
+/// `$XDG_CONFIG_HOME/pin-board`, falling back to `~/.config/pin-board`.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| home_dir().map(|h| h.join(".config")))?;
+    Some(base.join("pin-board"))
+}
+
+pub fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .filter(|p| !p.as_os_str().is_empty())
+}
+
+pub fn quarantine_path(store: &Path, extension: &str) -> PathBuf {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    store.with_file_name(format!("pins.corrupt-{stamp}.{extension}"))
+}
+
+/// Writes to a temp file and renames it over `path`, so a crash leaves
+/// either the old or the new contents, never half of them.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path)?;
+
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        fs::File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// The board as a single JSON document on disk (see docs/STORAGE.md).
+pub struct JsonStore {
+    path: PathBuf,
+}
+
+impl JsonStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn backup_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".{n}"));
+        self.path.with_file_name(name)
+    }
+
+    fn write(&self, contents: &str) -> io::Result<()> {
+        self.rotate_backups()?;
+        write_atomic(&self.path, contents.as_bytes())
+    }
+
+    // Single-pin edits go through the whole document; there is no cheaper way
+    // to change one entry of a JSON array on disk.
+    fn edit_pins(&mut self, f: impl FnOnce(&mut Vec<Value>)) -> io::Result<()> {
+        let text = self.load()?.unwrap_or_else(|| "{}".to_string());
+        let mut doc: Value = serde_json::from_str(&text).map_err(io::Error::other)?;
+        let obj = doc.as_object_mut().ok_or_else(|| io::Error::other("board is not a JSON object"))?;
+        let pins = obj.entry("pins").or_insert_with(|| Value::Array(Vec::new()));
+        let Value::Array(pins) = pins else {
+            return Err(io::Error::other("\"pins\" is not an array"));
+        };
+        f(pins);
+        self.save(&doc)
+    }
+
+    // pins.json.1 is the newest backup, pins.json.5 the oldest. Saves happen
+    // often, so we only rotate once the newest backup is older than BACKUP_INTERVAL.
+    fn rotate_backups(&self) -> io::Result<()> {
+        if !self.path.exists() {
+            return Ok(());
+        }
+        let newest = self.backup_path(1);
+        let fresh = fs::metadata(&newest)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| SystemTime::now().duration_since(t).ok())
+            .is_some_and(|age| age < BACKUP_INTERVAL);
+        if fresh {
+            return Ok(());
+        }
+
+        for n in (1..BACKUP_COUNT).rev() {
+            let from = self.backup_path(n);
+            if from.exists() {
+                fs::rename(&from, self.backup_path(n + 1))?;
+            }
+        }
+        fs::copy(&self.path, &newest)?;
+        Ok(())
+    }
+}
+
+impl PinStore for JsonStore {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&self) -> io::Result<Option<String>> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => Ok(Some(text)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&mut self, doc: &Value) -> io::Result<()> {
+        let text = serde_json::to_string_pretty(doc).map_err(io::Error::other)?;
+        self.write(&text)
+    }
+
+    fn upsert(&mut self, pin: &Pin) -> io::Result<()> {
+        let value = serde_json::to_value(pin).map_err(io::Error::other)?;
+        self.edit_pins(|pins| {
+            match pins.iter_mut().find(|p| p["id"] == value["id"]) {
+                Some(existing) => *existing = value,
+                None => pins.push(value),
+            }
+        })
+    }
+
+    fn delete(&mut self, id: &str) -> io::Result<()> {
+        self.edit_pins(|pins| pins.retain(|p| p["id"] != id))
+    }
+
+    fn scrub(&mut self) -> io::Result<()> {
+        for n in 1..=BACKUP_COUNT {
+            match fs::remove_file(self.backup_path(n)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        scrub_eframe_board()
+    }
+
+    fn quarantine(&mut self, raw: &str) -> io::Result<PathBuf> {
+        let target = quarantine_path(&self.path, "json");
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        if self.path.exists() {
+            fs::rename(&self.path, &target)?;
+        } else {
+            fs::write(&target, raw)?;
+        }
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_store(name: &str) -> JsonStore {
+        let dir = std::env::temp_dir().join(format!("pin-board-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        JsonStore::new(dir.join("pins.json"))
+    }
+
+    fn read(path: &Path) -> Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    // As if the newest backup had been taken longer ago than BACKUP_INTERVAL.
+    fn age_backups(store: &JsonStore) {
+        if let Ok(file) = fs::File::options().write(true).open(store.backup_path(1)) {
+            file.set_modified(SystemTime::now() - BACKUP_INTERVAL - Duration::from_secs(1)).unwrap();
+        }
+    }
+
+    #[test]
+    fn rotates_at_most_five_backups() {
+        let mut store = temp_store("json-backups");
+        store.save(&json!({ "n": 0 })).unwrap();
+        assert!(!store.backup_path(1).exists());
+
+        // Saves within the interval leave the newest backup alone.
+        store.save(&json!({ "n": 1 })).unwrap();
+        store.save(&json!({ "n": 2 })).unwrap();
+        assert_eq!(read(&store.backup_path(1)), json!({ "n": 0 }));
+        assert!(!store.backup_path(2).exists());
+
+        for n in 3..=9 {
+            age_backups(&store);
+            store.save(&json!({ "n": n })).unwrap();
+        }
+        assert_eq!(read(store.path()), json!({ "n": 9 }));
+        let backups: Vec<Value> = (1..=BACKUP_COUNT).map(|n| read(&store.backup_path(n))).collect();
+        assert_eq!(backups, [json!({ "n": 8 }), json!({ "n": 7 }), json!({ "n": 6 }), json!({ "n": 5 }), json!({ "n": 4 })]);
+        assert!(!store.backup_path(BACKUP_COUNT + 1).exists());
+
+        let dir = store.path().parent().unwrap();
+        let files = fs::read_dir(dir).unwrap().count();
+        assert_eq!(files, 1 + BACKUP_COUNT, "no temp file is left behind");
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn writes_single_pins_into_the_document() {
+        let mut store = temp_store("json-pins");
+        let mut pin = Pin::new("a".to_string(), String::new(), None);
+        store.upsert(&pin).unwrap();
+        pin.title = "A".to_string();
+        store.upsert(&pin).unwrap();
+        let other = Pin::new("b".to_string(), String::new(), None);
+        store.upsert(&other).unwrap();
+        store.delete(&pin.id).unwrap();
+
+        let doc: Value = serde_json::from_str(&store.load().unwrap().unwrap()).unwrap();
+        let titles: Vec<&str> = doc["pins"].as_array().unwrap().iter().map(|p| p["title"].as_str().unwrap()).collect();
+        assert_eq!(titles, ["b"]);
+        let _ = fs::remove_dir_all(store.path().parent().unwrap());
+    }
+}
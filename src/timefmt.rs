@@ -0,0 +1,97 @@
+use chrono::{DateTime, Datelike, Local, Utc};
+
+/// "just now", "5 min ago", "yesterday", "3 days ago", then a plain date.
+/// Times in the future, as from a clock that runs ahead, get the plain date
+/// unless they are less than a minute away.
+pub fn relative(t: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let (local, today) = (t.with_timezone(&Local), now.with_timezone(&Local));
+    let secs = (now - t).num_seconds();
+    if secs < -60 {
+        return date(local, today);
+    }
+    if secs < 60 {
+        return "just now".to_string();
+    }
+    let mins = secs / 60;
+    if mins < 60 {
+        return format!("{mins} min ago");
+    }
+    let hours = mins / 60;
+    if hours < 24 {
+        return if hours == 1 { "1 hour ago".to_string() } else { format!("{hours} hours ago") };
+    }
+
+    let days = (today.date_naive() - local.date_naive()).num_days();
+    match days {
+        ..=1 => "yesterday".to_string(),
+        2..=6 => format!("{days} days ago"),
+        _ => date(local, today),
+    }
+}
+
+fn date(local: DateTime<Local>, today: DateTime<Local>) -> String {
+    if local.year() == today.year() { local.format("%b %-d").to_string() } else { local.format("%b %-d, %Y").to_string() }
+}
+
+pub fn clock(t: DateTime<Utc>, h24: bool) -> String {
+    let local = t.with_timezone(&Local);
+    if h24 { local.format("%H:%M").to_string() } else { local.format("%I:%M %p").to_string() }
+}
+
+pub fn absolute(t: DateTime<Utc>, h24: bool) -> String {
+    format!("{}, {}", t.with_timezone(&Local).format("%a %b %-d %Y"), clock(t, h24))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // Local times, so the tests read the same in any time zone.
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Local.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn words_recent_times() {
+        let now = at(2026, 6, 17, 15, 0);
+        assert_eq!(relative(now, now), "just now");
+        assert_eq!(relative(at(2026, 6, 17, 14, 59), now), "1 min ago");
+        assert_eq!(relative(at(2026, 6, 17, 14, 15), now), "45 min ago");
+        assert_eq!(relative(at(2026, 6, 17, 14, 0), now), "1 hour ago");
+        assert_eq!(relative(at(2026, 6, 16, 16, 0), now), "23 hours ago");
+        assert_eq!(relative(at(2026, 6, 16, 9, 0), now), "yesterday");
+        assert_eq!(relative(at(2026, 6, 14, 9, 0), now), "3 days ago");
+        assert_eq!(relative(at(2026, 6, 11, 9, 0), now), "6 days ago");
+        assert_eq!(relative(at(2026, 6, 10, 9, 0), now), "Jun 10");
+        assert_eq!(relative(at(2025, 12, 31, 9, 0), now), "Dec 31, 2025");
+    }
+
+    #[test]
+    fn counts_days_by_the_calendar() {
+        // Two hours, but across midnight.
+        assert_eq!(relative(at(2026, 6, 16, 23, 0), at(2026, 6, 17, 1, 0)), "2 hours ago");
+        // A day and an hour, but two midnights back.
+        assert_eq!(relative(at(2026, 6, 15, 23, 30), at(2026, 6, 17, 0, 30)), "2 days ago");
+        assert_eq!(relative(at(2026, 6, 16, 0, 0), at(2026, 6, 17, 23, 59)), "yesterday");
+    }
+
+    #[test]
+    fn dates_times_in_the_future() {
+        let now = at(2026, 6, 17, 15, 0);
+        assert_eq!(relative(now + chrono::Duration::seconds(30), now), "just now");
+        assert_eq!(relative(at(2026, 6, 17, 18, 0), now), "Jun 17");
+        assert_eq!(relative(at(2027, 1, 2, 9, 0), now), "Jan 2, 2027");
+    }
+
+    #[test]
+    fn formats_12_and_24_hour_clocks() {
+        let t = at(2026, 6, 7, 9, 5);
+        assert_eq!(clock(t, true), "09:05");
+        assert_eq!(clock(t, false), "09:05 AM");
+        assert_eq!(clock(at(2026, 6, 7, 21, 30), false), "09:30 PM");
+        assert_eq!(clock(at(2026, 6, 7, 0, 0), true), "00:00");
+        assert_eq!(clock(at(2026, 6, 7, 0, 0), false), "12:00 AM");
+        assert_eq!(absolute(at(2026, 6, 7, 21, 30), true), "Sun Jun 7 2026, 21:30");
+    }
+}
@@ -0,0 +1,280 @@
+//! Pins as todo.txt tasks, one per line:
+//!
+//! ```text
+//! (A) 2026-10-01 Deploy at 5 +release @work due:2026-10-18 pin:6f1c2a9e-...
+//! x 2026-10-18 2026-10-02 Renew passport pin:0b7d...
+//! ```
+//!
+//! Only the title and tags travel; the pin's text stays on the board. Each
+//! `+project` word is a tag, and so is each `@context` word, kept with its
+//! `@` so it is written back the same way.
+
+use chrono::{Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::pin::Pin;
+use crate::store;
+
+const DATE: &str = "%Y-%m-%d";
+// How often a mirrored file is compared with the board.
+const SYNC_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct Task {
+    pub id: Option<String>,
+    pub text: String,
+    pub completed: bool,
+    pub created_on: Option<NaiveDate>,
+    pub due: Option<NaiveDate>,
+    pub tags: Vec<String>,
+}
+
+impl Task {
+    pub fn into_pin(self) -> Pin {
+        let mut pin = Pin::new(String::new(), String::new(), None);
+        if let Some(id) = &self.id {
+            pin.id = id.clone();
+        }
+        if let Some(t) = self.created_on.and_then(local_midnight) {
+            pin.created_at = t;
+        }
+        self.apply(&mut pin);
+        pin
+    }
+
+    /// Copies the task onto `pin`, leaving its text and looks alone.
+    /// Returns whether anything changed.
+    pub fn apply(&self, pin: &mut Pin) -> bool {
+        let before = (pin.title.clone(), pin.is_completed, pin.deadline, pin.tags.clone());
+
+        // A pin without a title is exported under its first line of text.
+        let untitled = pin.title.is_empty() && pin.content.lines().next() == Some(self.text.as_str());
+        if !untitled {
+            pin.title = self.text.clone();
+        }
+        if pin.is_completed != self.completed {
+            pin.set_completed(self.completed);
+        }
+        if pin.deadline.map(|d| d.date_naive()) != self.due {
+            // A due date has no time; count it as due by the end of that day.
+            pin.deadline = self.due.and_then(|d| Local.from_local_datetime(&d.and_time(NaiveTime::from_hms_opt(23, 59, 0)?)).earliest());
+        }
+
+        if !self.tags.iter().map(|t| t.to_lowercase()).eq(pin.tags.iter().map(|t| t.to_lowercase())) {
+            pin.tags.clear();
+            for tag in &self.tags { pin.add_tag(tag); }
+        }
+
+        let changed = before != (pin.title.clone(), pin.is_completed, pin.deadline, pin.tags.clone());
+        if changed {
+            pin.modified_at = Utc::now();
+        }
+        changed
+    }
+}
+
+fn local_midnight(date: NaiveDate) -> Option<chrono::DateTime<Utc>> {
+    Local.from_local_datetime(&date.and_time(NaiveTime::MIN)).earliest().map(|t| t.with_timezone(&Utc))
+}
+
+pub fn parse(text: &str) -> Vec<Task> {
+    text.lines().filter_map(parse_line).collect()
+}
+
+fn parse_line(line: &str) -> Option<Task> {
+    let mut words = line.split_whitespace().peekable();
+    words.peek()?;
+    let date = |w: Option<&&str>| w.and_then(|w| NaiveDate::parse_from_str(w, DATE).ok());
+
+    let mut text = Vec::new();
+    let completed = words.next_if_eq(&"x").is_some();
+    if completed && date(words.peek()).is_some() {
+        // Completion date; the pin keeps its own.
+        words.next();
+    }
+    if !completed {
+        if let Some(priority) = words.next_if(|w| is_priority(w)) {
+            text.push(priority);
+        }
+    }
+    let created_on = date(words.peek());
+    if created_on.is_some() {
+        words.next();
+    }
+
+    let (mut id, mut due, mut tags) = (None, None, Vec::new());
+    for word in words {
+        if let Some(tag) = word.strip_prefix('+').filter(|t| !t.is_empty()) {
+            tags.push(tag.to_string());
+        } else if word.len() > 1 && word.starts_with('@') {
+            tags.push(word.to_string());
+        } else if let Some(v) = word.strip_prefix("pin:") {
+            id = Some(v.to_string());
+        } else if let Some(d) = word.strip_prefix("due:").and_then(|v| NaiveDate::parse_from_str(v, DATE).ok()) {
+            due = Some(d);
+        } else {
+            text.push(word);
+        }
+    }
+    Some(Task { id, text: text.join(" "), completed, created_on, due, tags })
+}
+
+fn is_priority(word: &str) -> bool {
+    let b = word.as_bytes();
+    b.len() == 3 && b[0] == b'(' && b[1].is_ascii_uppercase() && b[2] == b')'
+}
+
+pub fn export(pins: &[Pin]) -> String {
+    pins.iter().map(|p| format_line(p) + "\n").collect()
+}
+
+fn format_line(pin: &Pin) -> String {
+    let title = if pin.title.is_empty() { pin.content.lines().next().unwrap_or("") } else { pin.title.lines().next().unwrap_or("") };
+    let mut words: Vec<String> = Vec::new();
+    let mut text = title.split_whitespace().peekable();
+
+    if pin.is_completed {
+        words.push("x".into());
+        words.push(pin.completed_at.unwrap_or(pin.modified_at).with_timezone(&Local).format(DATE).to_string());
+    } else if let Some(priority) = text.next_if(|w| is_priority(w)) {
+        words.push(priority.into());
+    }
+    words.push(pin.created_at.with_timezone(&Local).format(DATE).to_string());
+    words.extend(text.map(str::to_string));
+    for tag in &pin.tags {
+        let word = if tag.starts_with('@') { tag.clone() } else { format!("+{tag}") };
+        if !words.iter().any(|w| w.eq_ignore_ascii_case(&word)) { words.push(word); }
+    }
+    if let Some(deadline) = pin.deadline {
+        words.push(format!("due:{}", deadline.format(DATE)));
+    }
+    words.push(format!("pin:{}", pin.id));
+    words.join(" ")
+}
+
+/// Keeps a todo.txt file and the board in step while the app runs.
+pub struct Mirror {
+    path: PathBuf,
+    // The file as we last read or wrote it; anything else is an outside edit.
+    last_text: Option<String>,
+    last_ids: HashSet<String>,
+    last_check: Option<Instant>,
+}
+
+/// An outside edit to the mirrored file.
+pub struct Changes {
+    pub tasks: Vec<Task>,
+    /// Pins whose lines were deleted from the file.
+    pub removed: Vec<String>,
+}
+
+impl Mirror {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path, last_text: None, last_ids: HashSet::new(), last_check: None }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn due(&mut self) -> bool {
+        if self.last_check.is_some_and(|t| t.elapsed() < SYNC_INTERVAL) {
+            return false;
+        }
+        self.last_check = Some(Instant::now());
+        true
+    }
+
+    /// Reads the file if it changed since we last saw it. The first read only
+    /// brings tasks in, so starting a mirror never deletes pins.
+    pub fn read_changes(&mut self) -> io::Result<Option<Changes>> {
+        let text = match std::fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if self.last_text.as_deref() == Some(text.as_str()) {
+            return Ok(None);
+        }
+
+        let tasks = parse(&text);
+        let ids: HashSet<String> = tasks.iter().filter_map(|t| t.id.clone()).collect();
+        let removed = if self.last_text.is_some() { self.last_ids.difference(&ids).cloned().collect() } else { Vec::new() };
+        self.last_text = Some(text);
+        self.last_ids = ids;
+        Ok(Some(Changes { tasks, removed }))
+    }
+
+    pub fn write(&mut self, pins: &[Pin]) -> io::Result<()> {
+        let text = export(pins);
+        if self.last_text.as_deref() == Some(text.as_str()) {
+            return Ok(());
+        }
+        store::write_atomic(&self.path, text.as_bytes())?;
+        self.last_ids = pins.iter().map(|p| p.id.clone()).collect();
+        self.last_text = Some(text);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_every_part_of_a_task() {
+        let task = parse_line("(A) 2026-10-01 Deploy at 5 +release @work due:2026-10-18 pin:6f1c").unwrap();
+        assert_eq!(task.id.as_deref(), Some("6f1c"));
+        assert_eq!(task.text, "(A) Deploy at 5");
+        assert!(!task.completed);
+        assert_eq!(task.created_on, NaiveDate::from_ymd_opt(2026, 10, 1));
+        assert_eq!(task.due, NaiveDate::from_ymd_opt(2026, 10, 18));
+        assert_eq!(task.tags, ["release", "@work"]);
+
+        let done = parse_line("x 2026-10-18 2026-10-02 Renew passport me@example.com @").unwrap();
+        assert!(done.completed);
+        assert_eq!(done.created_on, NaiveDate::from_ymd_opt(2026, 10, 2));
+        assert_eq!(done.text, "Renew passport me@example.com @");
+        assert!(done.tags.is_empty());
+        assert!(parse_line("   ").is_none());
+    }
+
+    #[test]
+    fn round_trips_through_a_line() {
+        let mut pin = Pin::new("(B) Call the bank".into(), "about the card".into(), None);
+        pin.add_tag("errands");
+        pin.add_tag("@phone");
+        pin.deadline = Local.with_ymd_and_hms(2026, 10, 20, 23, 59, 0).earliest();
+        let line = format_line(&pin);
+        assert!(line.starts_with("(B) "));
+        assert!(line.contains(" Call the bank +errands @phone due:2026-10-20 pin:"));
+
+        let task = parse_line(&line).unwrap();
+        let mut back = task.into_pin();
+        assert_eq!(back.id, pin.id);
+        assert_eq!(back.title, pin.title);
+        assert_eq!(back.tags, pin.tags);
+        assert_eq!(back.deadline, pin.deadline);
+        assert_eq!(back.created_at.with_timezone(&Local).date_naive(), pin.created_at.with_timezone(&Local).date_naive());
+        assert!(!parse_line(&line).unwrap().apply(&mut back));
+
+        pin.set_completed(true);
+        let task = parse_line(&format_line(&pin)).unwrap();
+        assert!(task.completed);
+        assert_eq!(task.text, "(B) Call the bank");
+        assert!(task.apply(&mut back));
+        assert!(back.is_completed);
+        assert_eq!(back.title, pin.title);
+    }
+
+    #[test]
+    fn untitled_pins_keep_their_text() {
+        let mut pin = Pin::new(String::new(), "Water the plants\nevery other day".into(), None);
+        let task = parse_line(&format_line(&pin)).unwrap();
+        assert_eq!(task.text, "Water the plants");
+        assert!(!task.apply(&mut pin));
+        assert!(pin.title.is_empty());
+    }
+}
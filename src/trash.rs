@@ -0,0 +1,27 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::pin::Pin;
+
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct TrashedPin {
+    pub pin: Pin,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl TrashedPin {
+    pub fn new(pin: Pin) -> Self {
+        Self { pin, deleted_at: Utc::now() }
+    }
+}
+
+/// Drops pins that have been in the trash for longer than `days`.
+/// Returns whether anything was purged.
+pub fn purge_expired(trash: &mut Vec<TrashedPin>, days: u32) -> bool {
+    let cutoff = Utc::now() - Duration::days(days as i64);
+    let before = trash.len();
+    trash.retain(|t| t.deleted_at > cutoff);
+    trash.len() != before
+}